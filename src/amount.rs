@@ -0,0 +1,156 @@
+/*
+ * AMOUNT - Satoshi-precise L-BTC amounts
+ *
+ * Amounts are kept as whole satoshis and never go through f64 on their
+ * way from the command line to the funding transaction. Accepted forms:
+ *   "0.1", "0.1 L-BTC", "0.1 btc"      decimal L-BTC, at most 8 decimals
+ *   "10000000 sat", "10000000 sats"    satoshis
+ *
+ * Displayed as L-BTC with 8 decimals ("0.10000000"), which is also how
+ * amounts are written in puzzle files. In JSON and in the database an
+ * Amount is its number of satoshis.
+ */
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const SATS_PER_BTC: u64 = 100_000_000;
+
+/// An L-BTC amount in satoshis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Amount(u64);
+
+impl Amount {
+    pub const ZERO: Amount = Amount(0);
+
+    pub const fn from_sat(sats: u64) -> Self {
+        Amount(sats)
+    }
+
+    pub const fn to_sat(self) -> u64 {
+        self.0
+    }
+
+    /// Parse a decimal L-BTC string such as "0.1" exactly.
+    pub fn from_btc_str(amount: &str) -> Result<Self> {
+        let amount = amount.trim();
+        let (whole, frac) = amount.split_once('.').unwrap_or((amount, ""));
+
+        if whole.is_empty() && frac.is_empty() {
+            return Err(anyhow::anyhow!("Invalid amount: {:?}", amount));
+        }
+        if !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(anyhow::anyhow!("Invalid amount: {:?}", amount));
+        }
+        if frac.len() > 8 {
+            return Err(anyhow::anyhow!(
+                "Invalid amount {:?}: L-BTC has at most 8 decimal places",
+                amount
+            ));
+        }
+
+        let whole: u64 = if whole.is_empty() { 0 } else { whole.parse()? };
+        let frac: u64 = if frac.is_empty() { 0 } else { format!("{:0<8}", frac).parse()? };
+
+        whole
+            .checked_mul(SATS_PER_BTC)
+            .and_then(|sats| sats.checked_add(frac))
+            .map(Amount)
+            .ok_or_else(|| anyhow::anyhow!("Amount {:?} is too large", amount))
+    }
+
+    /// An amount from an RPC result ("value", "amount"), which the node
+    /// always returns in L-BTC with at most 8 decimals.
+    pub fn from_rpc(value: &serde_json::Value) -> Option<Self> {
+        if let Some(btc) = value.as_u64() {
+            return btc.checked_mul(SATS_PER_BTC).map(Amount);
+        }
+        let btc = value.as_f64().filter(|v| v.is_finite() && *v >= 0.0)?;
+        // Exact for every 8-decimal value below 90 million L-BTC
+        Some(Amount((btc * SATS_PER_BTC as f64).round() as u64))
+    }
+
+    /// The amount as a JSON number in L-BTC, for RPC arguments.
+    pub fn to_rpc(self) -> serde_json::Number {
+        serde_json::from_str(&self.to_string()).expect("8-decimal amount is a valid JSON number")
+    }
+
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        self.0.checked_add(other.0).map(Amount)
+    }
+
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        self.0.checked_sub(other.0).map(Amount)
+    }
+}
+
+impl FromStr for Amount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (number, unit) = match s.split_once(char::is_whitespace) {
+            Some((number, unit)) => (number, unit.trim().to_lowercase()),
+            None => (s, String::new()),
+        };
+
+        match unit.as_str() {
+            "" | "btc" | "lbtc" | "l-btc" => Amount::from_btc_str(number),
+            "sat" | "sats" => number
+                .parse()
+                .map(Amount)
+                .map_err(|_| anyhow::anyhow!("Invalid satoshi amount: {:?}", number)),
+            other => Err(anyhow::anyhow!("Unknown amount unit {:?} (use L-BTC or sat)", other)),
+        }
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:08}", self.0 / SATS_PER_BTC, self.0 % SATS_PER_BTC)
+    }
+}
+
+impl std::iter::Sum for Amount {
+    fn sum<I: Iterator<Item = Amount>>(iter: I) -> Self {
+        Amount(iter.map(|a| a.0).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_l_btc_with_at_most_8_decimals() {
+        assert_eq!("0.1".parse::<Amount>().unwrap(), Amount::from_sat(10_000_000));
+        assert_eq!("0.12345678".parse::<Amount>().unwrap(), Amount::from_sat(12_345_678));
+        assert_eq!("1.5 L-BTC".parse::<Amount>().unwrap(), Amount::from_sat(150_000_000));
+        assert!("0.123456789".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn parses_satoshis() {
+        assert_eq!("50000 sat".parse::<Amount>().unwrap(), Amount::from_sat(50_000));
+        assert_eq!("1 sats".parse::<Amount>().unwrap(), Amount::from_sat(1));
+        assert!("0.5 sat".parse::<Amount>().is_err());
+        assert!("5 bits".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn refuses_overflow_and_negative_amounts() {
+        assert!("184467440737.09551616".parse::<Amount>().is_err());
+        assert!("18446744073709551616 sat".parse::<Amount>().is_err());
+        assert!("-0.1".parse::<Amount>().is_err());
+        assert!("-1 sat".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn displays_8_decimals() {
+        assert_eq!(Amount::from_sat(10_000_000).to_string(), "0.10000000");
+        assert_eq!("0.1".parse::<Amount>().unwrap().to_string().parse::<Amount>().unwrap(), Amount::from_sat(10_000_000));
+    }
+}
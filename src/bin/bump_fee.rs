@@ -0,0 +1,17 @@
+/*
+ * BUMP FEE - Alias of `puzzle-hunt bump-fee`
+ *
+ * Usage:
+ *   cargo run --bin bump-fee -- <spend_txid | puzzle_file.json> [--fee-rate <sat/vB> | --fee <sats>]
+ *
+ * Example:
+ *   cargo run --bin bump-fee -- puzzle_2cf24dba.json --fee-rate 2
+ *
+ * The positional argument is --spend; see src/commands/bump_fee.rs.
+ */
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    puzzle_hunt::commands::alias("bump-fee", &["spend"])
+}
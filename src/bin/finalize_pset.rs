@@ -0,0 +1,17 @@
+/*
+ * FINALIZE PSET - Alias of `puzzle-hunt finalize-pset`
+ *
+ * Usage:
+ *   cargo run --bin finalize-pset -- <pset_file | base64> [--dry-run]
+ *
+ * Example:
+ *   cargo run --bin finalize-pset -- solve.pset
+ *
+ * The positional argument is --pset; see src/commands/finalize_pset.rs.
+ */
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    puzzle_hunt::commands::alias("finalize-pset", &["pset"])
+}
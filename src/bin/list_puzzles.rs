@@ -0,0 +1,14 @@
+/*
+ * LIST PUZZLES - Alias of `puzzle-hunt list`
+ *
+ * Usage:
+ *   cargo run --bin list-puzzles -- [--auto] [--json] [--network <chain>] [--type <contract>] [--status <status>]
+ *
+ * See src/commands/list.rs.
+ */
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    puzzle_hunt::commands::alias("list", &[])
+}
@@ -0,0 +1,17 @@
+/*
+ * PUZZLE DB - Alias of `puzzle-hunt db`
+ *
+ * Usage:
+ *   cargo run --bin puzzle-db -- [--output json] <import | list | show | set-status> [args]
+ *
+ * Example:
+ *   cargo run --bin puzzle-db -- show a0dc65ff
+ *
+ * See src/commands/db.rs.
+ */
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    puzzle_hunt::commands::alias("db", &[])
+}
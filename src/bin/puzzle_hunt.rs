@@ -0,0 +1,21 @@
+/*
+ * PUZZLE HUNT - Create, solve and manage Simplicity puzzles
+ *
+ * Usage:
+ *   cargo run --bin puzzle-hunt -- <command> [options]
+ *
+ * Examples:
+ *   cargo run --bin puzzle-hunt -- create --secret "satoshi" --amount 0.1 --hint "Bitcoin's creator"
+ *   cargo run --bin puzzle-hunt -- solve --puzzle puzzle_a0dc65ff.json --secret "satoshi" --to tex1q...
+ *   cargo run --bin puzzle-hunt -- --output json list --status active
+ *   cargo run --bin puzzle-hunt -- completions bash > puzzle-hunt.bash
+ *
+ * Run with --help, or <command> --help, for every option.
+ */
+
+use anyhow::Result;
+use puzzle_hunt::commands;
+
+fn main() -> Result<()> {
+    commands::run_from(std::env::args_os())
+}
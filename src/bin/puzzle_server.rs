@@ -0,0 +1,86 @@
+/*
+ * PUZZLE SERVER - Local HTTP JSON API for the puzzle database
+ *
+ * Usage:
+ *   cargo run --bin puzzle-server -- [--bind <addr:port>]
+ *
+ * Examples:
+ *   cargo run --bin puzzle-server
+ *   curl http://127.0.0.1:8080/puzzles
+ *   curl http://127.0.0.1:8080/puzzles/2cf24dba/program
+ *   curl -X POST http://127.0.0.1:8080/puzzles/2cf24dba/solve \
+ *        -d '{"secret": "satoshi", "to": "el1q..."}'
+ *
+ * Serves the database in PUZZLE_DB (or puzzles.db) against the node in
+ * config.env; point both at a regtest setup to try it locally. Routes
+ * are described in server.rs. It binds to localhost by default: solve
+ * requests spend from the node, so do not expose it without a proxy.
+ *
+ * GET replies allow any origin, so a puzzle board page can read them;
+ * POST replies do not, so another site cannot drive solves or
+ * registrations from a visitor's browser.
+ *
+ * Requests are logged to stderr.
+ */
+
+use anyhow::Result;
+use clap::Parser;
+use puzzle_hunt::chain::ElementsCli;
+use puzzle_hunt::output::{self, OutputFormat};
+use puzzle_hunt::server::{Api, MAX_BODY};
+use puzzle_hunt::store::PuzzleStore;
+use std::io::Read;
+use tiny_http::{Header, Response, Server};
+
+#[derive(Debug, Parser)]
+#[command(name = "puzzle-server", version, about = "HTTP JSON API for the puzzle database")]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: String,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    // Solve progress goes to stderr with the request log
+    output::init(OutputFormat::Json);
+
+    let api = Api::new(ElementsCli::from_config()?, PuzzleStore::open_default()?);
+    let server = Server::http(&args.bind).map_err(|e| anyhow::anyhow!("Failed to listen on {}: {}", args.bind, e))?;
+    eprintln!("🌐 Puzzle server listening on http://{}", args.bind);
+
+    let json = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("valid header");
+    let cors = Header::from_bytes(&b"Access-Control-Allow-Origin"[..], &b"*"[..]).expect("valid header");
+
+    for mut request in server.incoming_requests() {
+        let method = request.method().as_str().to_string();
+        let url = request.url().to_string();
+
+        let mut body = String::new();
+        let reply = match request.as_reader().take(MAX_BODY as u64 + 1).read_to_string(&mut body) {
+            Ok(_) if body.len() > MAX_BODY => None,
+            Ok(_) => Some(api.handle(&method, &url, &body)),
+            Err(_) => None,
+        };
+        let (status, body) = match reply {
+            Some(reply) => (reply.status, reply.body),
+            None => (
+                400,
+                serde_json::json!({ "ok": false, "error": { "code": "invalid_arguments", "message": "Unreadable or too large request body" } }),
+            ),
+        };
+
+        eprintln!("{} {} -> {}", method, url, status);
+        let mut response = Response::from_string(serde_json::to_string_pretty(&body)?)
+            .with_status_code(status)
+            .with_header(json.clone());
+        if method == "GET" {
+            response = response.with_header(cors.clone());
+        }
+        if let Err(e) = request.respond(response) {
+            eprintln!("⚠️  Failed to respond: {}", e);
+        }
+    }
+
+    Ok(())
+}
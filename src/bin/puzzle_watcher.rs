@@ -0,0 +1,19 @@
+/*
+ * PUZZLE WATCHER - Alias of `puzzle-hunt watch`
+ *
+ * Usage:
+ *   cargo run --bin puzzle-watcher -- [--interval <secs>] [--webhook <url>] [--hook <command>] [--once]
+ *                                     [--auto-bump [--max-fee-rate <sat/vB>]] [--release-hints [--identity <file>]]
+ *                                     [--unlock-hints]
+ *
+ * Example:
+ *   cargo run --bin puzzle-watcher -- --interval 10 --webhook http://127.0.0.1:8080/events
+ *
+ * See src/commands/watch.rs.
+ */
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    puzzle_hunt::commands::alias("watch", &[])
+}
@@ -0,0 +1,19 @@
+/*
+ * REVEAL SECRET - Alias of `puzzle-hunt reveal-secret`
+ *
+ * Usage:
+ *   cargo run --bin reveal-secret -- <secret_file.json> [identity_file]
+ *   cargo run --bin reveal-secret -- --encrypt <secret_file.json>
+ *
+ * Example:
+ *   cargo run --bin reveal-secret -- puzzle_a0dc65ff_SECRET.json ~/.age/puzzles.key
+ *
+ * The positional arguments are --file and --identity; see
+ * src/commands/reveal_secret.rs.
+ */
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    puzzle_hunt::commands::alias("reveal-secret", &["file", "identity"])
+}
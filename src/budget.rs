@@ -0,0 +1,90 @@
+/*
+ * BUDGET - Simplicity cost versus witness weight
+ *
+ * Elements only runs a Simplicity program whose static cost bound fits
+ * in the budget paid for by the input's witness weight. Small programs
+ * with small witnesses can be "too expensive for their size"; the fix
+ * is an annex that pads the witness until the budget covers the cost.
+ *
+ * The witness stack here is the full Taproot script-path stack:
+ *   [simplicity witness, program, script, control block, (annex)]
+ */
+
+use crate::progress;
+use serde::Serialize;
+use simplicity::jet::Elements;
+use simplicity::RedeemNode;
+
+/// Cost bound and budget of one Simplicity spend.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetReport {
+    /// Static cost bound of the redeem program
+    pub cost: String,
+    /// Serialized size of the witness stack, which pays for the cost
+    pub witness_weight: usize,
+    /// Whether the cost fits the budget of this witness
+    pub within_budget: bool,
+    /// Annex bytes needed to make it fit, if any
+    pub padding_needed: Option<usize>,
+    /// Whether the cost is below the consensus maximum at all
+    pub consensus_valid: bool,
+}
+
+/// Budget report for `program` spent with `witness_stack`.
+pub fn report(program: &RedeemNode<Elements>, witness_stack: &[Vec<u8>]) -> BudgetReport {
+    let cost = program.bounds().cost;
+    // simplicity takes the stack as a Vec
+    let stack = witness_stack.to_vec();
+    BudgetReport {
+        cost: cost.to_string(),
+        witness_weight: witness_weight(witness_stack),
+        within_budget: cost.is_budget_valid(&stack),
+        padding_needed: cost.get_padding(&stack).map(|annex| annex.len()),
+        consensus_valid: cost.is_consensus_valid(),
+    }
+}
+
+/// Append the annex `program` needs to `witness_stack`, if any.
+/// Returns the annex size.
+pub fn pad_witness(program: &RedeemNode<Elements>, witness_stack: &mut Vec<Vec<u8>>) -> Option<usize> {
+    let annex = program.bounds().cost.get_padding(witness_stack)?;
+    let len = annex.len();
+    witness_stack.push(annex);
+    Some(len)
+}
+
+/// Serialized size of a witness stack, length prefixes included.
+pub fn witness_weight(witness_stack: &[Vec<u8>]) -> usize {
+    varint_len(witness_stack.len())
+        + witness_stack
+            .iter()
+            .map(|item| varint_len(item.len()) + item.len())
+            .sum::<usize>()
+}
+
+fn varint_len(n: usize) -> usize {
+    match n {
+        0..=0xfc => 1,
+        0xfd..=0xffff => 3,
+        0x10000..=0xffff_ffff => 5,
+        _ => 9,
+    }
+}
+
+impl BudgetReport {
+    /// Print the report in the binaries' indented style.
+    pub fn print(&self) {
+        progress!("   💸 Static cost bound: {}", self.cost);
+        progress!("   ⚖️  Witness weight: {} WU", self.witness_weight);
+        if !self.consensus_valid {
+            progress!("   ❌ Cost exceeds the consensus maximum: this program can never be spent");
+        } else if self.within_budget {
+            progress!("   ✅ Cost fits the witness budget, no annex needed");
+        } else {
+            match self.padding_needed {
+                Some(bytes) => progress!("   📎 Needs {} bytes of annex padding to fit the budget", bytes),
+                None => progress!("   ⚠️  Cost does not fit the witness budget"),
+            }
+        }
+    }
+}
@@ -0,0 +1,347 @@
+/*
+ * CAMPAIGN - Many puzzles created and funded together
+ *
+ * A campaign file lists the puzzles of an event. YAML:
+ *
+ *   name: satoshi-day
+ *   puzzles:
+ *     - secret: satoshi
+ *       amount: "0.1"
+ *       hint: Bitcoin's creator
+ *       hints:
+ *         - at: 1200
+ *           hint: It is a name
+ *     - secret: moon
+ *       locked_hints:
+ *         - after: 1
+ *           hint: Look up
+ *       amount: "50000 sat"
+ *       contract: puzzle_jackpot
+ *       confidential: true
+ *
+ * or CSV with a header row (the file name is the campaign name; hint
+ * schedules need YAML):
+ *
+ *   secret,amount,hint,contract,confidential
+ *   satoshi,0.1,Bitcoin's creator,,
+ *
+ * Amounts are strings written like --amount, or whole satoshis: 50000
+ * is 50000 sat in both formats, and an unquoted 0.1 in YAML is refused
+ * because YAML reads it as a float.
+ *
+ * The contract defaults to puzzle_jackpot and must be one create can
+ * build (contract::CONTRACTS). Unknown fields are rejected rather than
+ * ignored.
+ *
+ * create --batch compiles every puzzle, funds them all in one
+ * transaction with one output per puzzle, and writes a manifest next to
+ * the campaign file (campaign_manifest.json) mapping each puzzle to its
+ * vout in that transaction.
+ *
+ * Locked hints (locked_hints.rs) chain the puzzles: "after: 1" encrypts
+ * a hint under a key derived from puzzle 1's secret, so solving puzzle 1
+ * unlocks it. The manifest lists each puzzle's unlock conditions, which
+ * is the shape of the hunt.
+ */
+
+use crate::amount::Amount;
+use crate::contract;
+use crate::hints::ScheduledHint;
+use crate::locked_hints::{LockedHintSpec, Unlock};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// The contract of a puzzle that does not name one.
+pub const DEFAULT_CONTRACT: &str = contract::CONTRACTS[0];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Campaign {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub puzzles: Vec<CampaignPuzzle>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CampaignPuzzle {
+    pub secret: String,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: Amount,
+    #[serde(default)]
+    pub hint: Option<String>,
+    /// Hints released later, see hints.rs
+    #[serde(default)]
+    pub hints: Vec<ScheduledHint>,
+    /// Hints unlocked by the solves of other puzzles, see locked_hints.rs
+    #[serde(default)]
+    pub locked_hints: Vec<LockedHintSpec>,
+    #[serde(default)]
+    pub contract: Option<String>,
+    /// Blind the prize and publish the blinding key in the puzzle file
+    #[serde(default)]
+    pub confidential: Option<bool>,
+}
+
+impl CampaignPuzzle {
+    pub fn contract(&self) -> &str {
+        self.contract.as_deref().filter(|c| !c.is_empty()).unwrap_or(DEFAULT_CONTRACT)
+    }
+}
+
+/// A YAML amount is a string written like on the command line ("0.1",
+/// "0.1 L-BTC", "50000 sat") or a whole number of satoshis. A bare 0.1 is
+/// a float and is refused rather than rounded.
+fn deserialize_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+    struct AmountVisitor;
+
+    impl serde::de::Visitor<'_> for AmountVisitor {
+        type Value = Amount;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("an amount string such as \"0.1\" or \"50000 sat\", or whole satoshis")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, text: &str) -> Result<Amount, E> {
+            text.parse().map_err(E::custom)
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, sats: u64) -> Result<Amount, E> {
+            Ok(Amount::from_sat(sats))
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, sats: i64) -> Result<Amount, E> {
+            u64::try_from(sats)
+                .map(Amount::from_sat)
+                .map_err(|_| E::custom(format!("amount cannot be negative: {}", sats)))
+        }
+
+        fn visit_f64<E: serde::de::Error>(self, btc: f64) -> Result<Amount, E> {
+            Err(E::custom(format!("write L-BTC amounts as strings (\"{}\") or whole satoshis", btc)))
+        }
+    }
+
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+/// A CSV row. Every cell is text, so the amount is read here rather than
+/// by deserialize_amount: a whole number is satoshis, as in YAML, and
+/// anything else is parsed like --amount.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CsvPuzzle {
+    secret: String,
+    amount: String,
+    #[serde(default)]
+    hint: Option<String>,
+    #[serde(default)]
+    contract: Option<String>,
+    #[serde(default)]
+    confidential: Option<bool>,
+}
+
+impl TryFrom<CsvPuzzle> for CampaignPuzzle {
+    type Error = anyhow::Error;
+
+    fn try_from(row: CsvPuzzle) -> Result<Self> {
+        let amount = if !row.amount.is_empty() && row.amount.chars().all(|c| c.is_ascii_digit()) {
+            Amount::from_sat(row.amount.parse().with_context(|| format!("Amount {:?} is too large", row.amount))?)
+        } else {
+            row.amount.parse()?
+        };
+        Ok(CampaignPuzzle {
+            secret: row.secret,
+            amount,
+            hint: row.hint,
+            hints: vec![],
+            locked_hints: vec![],
+            contract: row.contract,
+            confidential: row.confidential,
+        })
+    }
+}
+
+/// Read a campaign from a .yaml/.yml or .csv file and check it.
+pub fn load(path: &Path) -> Result<Campaign> {
+    let data = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+
+    let campaign = match extension.as_str() {
+        "yaml" | "yml" => serde_yaml::from_str(&data).context("Invalid campaign YAML")?,
+        "csv" => {
+            let puzzles = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(data.as_bytes())
+                .deserialize::<CsvPuzzle>()
+                .enumerate()
+                .map(|(i, row)| {
+                    row.map_err(anyhow::Error::from)
+                        .and_then(CampaignPuzzle::try_from)
+                        .with_context(|| format!("Invalid campaign CSV, puzzle {}", i + 1))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Campaign {
+                name: path.file_stem().and_then(|s| s.to_str()).map(str::to_string),
+                puzzles,
+            }
+        }
+        _ => return Err(anyhow::anyhow!("Campaign file must be .yaml, .yml or .csv: {}", path.display())),
+    };
+    check(&campaign)?;
+    Ok(campaign)
+}
+
+fn check(campaign: &Campaign) -> Result<()> {
+    if campaign.puzzles.is_empty() {
+        return Err(anyhow::anyhow!("Campaign has no puzzles"));
+    }
+    let mut secrets = HashSet::new();
+    for (i, puzzle) in campaign.puzzles.iter().enumerate() {
+        if !contract::CONTRACTS.contains(&puzzle.contract()) {
+            return Err(anyhow::anyhow!(
+                "Puzzle {}: contract {} cannot be created (supported: {})",
+                i + 1,
+                puzzle.contract(),
+                contract::CONTRACTS.join(", ")
+            ));
+        }
+        if puzzle.amount == Amount::ZERO {
+            return Err(anyhow::anyhow!("Puzzle {}: amount must be positive", i + 1));
+        }
+        for hint in &puzzle.hints {
+            hint.check().with_context(|| format!("Puzzle {}", i + 1))?;
+        }
+        for locked in &puzzle.locked_hints {
+            if locked.after == i + 1 {
+                return Err(anyhow::anyhow!("Puzzle {}: a hint cannot be locked behind its own puzzle", i + 1));
+            }
+            if locked.after == 0 || locked.after > campaign.puzzles.len() {
+                return Err(anyhow::anyhow!("Puzzle {}: no puzzle {} to lock a hint behind", i + 1, locked.after));
+            }
+        }
+        // The same secret would give the same address twice
+        if !secrets.insert(puzzle.secret.as_str()) {
+            return Err(anyhow::anyhow!("Puzzle {}: secret \"{}\" is used twice", i + 1, puzzle.secret));
+        }
+    }
+    Ok(())
+}
+
+/// Where the manifest of `campaign_file` goes: campaign.yaml -> campaign_manifest.json
+pub fn manifest_path(campaign_file: &Path) -> PathBuf {
+    let stem = campaign_file.file_stem().and_then(|s| s.to_str()).unwrap_or("campaign");
+    campaign_file.with_file_name(format!("{}_manifest.json", stem))
+}
+
+/// One funded puzzle of a campaign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub target_hash: String,
+    pub contract: String,
+    pub address: String,
+    pub vout: u32,
+    pub amount_sats: Amount,
+    pub cmr: String,
+    pub public_file: String,
+    pub secret_file: String,
+    /// What unlocks each of the puzzle's locked hints
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locked_hints: Vec<Unlock>,
+}
+
+/// What create --batch funded, and where each puzzle ended up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: Option<String>,
+    pub network: String,
+    pub funding_txid: String,
+    pub total_sats: Amount,
+    pub created_at: String,
+    pub puzzles: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&data).context("Invalid campaign manifest")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a campaign file for one test and load it.
+    fn load_text(test: &str, extension: &str, text: &str) -> Result<Campaign> {
+        let dir = std::env::temp_dir().join(format!("puzzle-hunt-campaign-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("campaign.{}", extension));
+        std::fs::write(&path, text).unwrap();
+        load(&path)
+    }
+
+    #[test]
+    fn reads_yaml_amounts_as_strings_or_satoshis() {
+        let campaign = load_text(
+            "yaml-amounts",
+            "yaml",
+            "puzzles:\n  - secret: a\n    amount: \"0.1\"\n  - secret: b\n    amount: 50000\n  - secret: c\n    amount: 50000 sat\n",
+        )
+        .unwrap();
+        let amounts: Vec<_> = campaign.puzzles.iter().map(|p| p.amount.to_sat()).collect();
+        assert_eq!(amounts, [10_000_000, 50_000, 50_000]);
+    }
+
+    #[test]
+    fn refuses_float_and_negative_yaml_amounts() {
+        assert!(load_text("yaml-float", "yaml", "puzzles:\n  - secret: a\n    amount: 0.1\n").is_err());
+        assert!(load_text("yaml-negative", "yaml", "puzzles:\n  - secret: a\n    amount: -5\n").is_err());
+    }
+
+    #[test]
+    fn reads_csv_amounts_like_yaml() {
+        let campaign = load_text(
+            "csv-amounts",
+            "csv",
+            "secret,amount,hint,confidential\na,0.1,First,\nb,50000,,true\nc,1 sat,,\n",
+        )
+        .unwrap();
+        let amounts: Vec<_> = campaign.puzzles.iter().map(|p| p.amount.to_sat()).collect();
+        assert_eq!(amounts, [10_000_000, 50_000, 1]);
+        assert_eq!(campaign.puzzles[0].hint.as_deref(), Some("First"));
+        assert_eq!(campaign.puzzles[1].confidential, Some(true));
+        assert_eq!(campaign.name.as_deref(), Some("campaign"));
+    }
+
+    #[test]
+    fn checks_the_contract_of_each_puzzle() {
+        let campaign = load_text(
+            "contract",
+            "yaml",
+            "puzzles:\n  - secret: a\n    amount: \"0.1\"\n  - secret: b\n    amount: \"0.1\"\n    contract: puzzle_jackpot\n",
+        )
+        .unwrap();
+        assert!(campaign.puzzles.iter().all(|p| p.contract() == DEFAULT_CONTRACT));
+
+        let unknown = "puzzles:\n  - secret: a\n    amount: \"0.1\"\n    contract: puzzle_chain\n";
+        assert!(load_text("unknown-contract", "yaml", unknown).is_err());
+        assert!(load_text("unknown-field", "yaml", "puzzles:\n  - secret: a\n    amount: \"0.1\"\n    prize: 1\n").is_err());
+    }
+
+    #[test]
+    fn locks_hints_only_behind_other_puzzles() {
+        let campaign = |locked: &str| {
+            format!("puzzles:\n  - secret: a\n    amount: \"0.1\"\n  - secret: b\n    amount: \"0.1\"\n    locked_hints:\n      - {}\n        hint: Look up\n", locked)
+        };
+        assert!(load_text("after", "yaml", &campaign("after: 1")).is_ok());
+        assert!(load_text("after-self", "yaml", &campaign("after: 2")).is_err());
+        assert!(load_text("after-missing", "yaml", &campaign("after: 3")).is_err());
+        assert!(load_text("height", "yaml", &campaign("height: 1500")).is_err());
+    }
+}
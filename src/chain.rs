@@ -0,0 +1,225 @@
+/*
+ * CHAIN - Access to the Elements node through elements-cli
+ *
+ * Reads the same config.env as the ./elements-cli wrapper script:
+ *   ELEMENTS_CLI_PATH   path to the elements-cli binary
+ *   ELEMENTS_CHAIN      liquidtestnet (default), liquidv1 or elementsregtest
+ *   WALLET_NAME         wallet used for funding (default: my_wallet)
+ *   ELEMENTS_DATA_DIR   optional -datadir
+ *   RPC_USER / RPC_PASSWORD / RPC_PORT   optional RPC credentials
+ *
+ * Environment variables with the same names override config.env.
+ * config.env is looked up in the working directory, or at the path
+ * in PUZZLE_CONFIG.
+ */
+
+use anyhow::{Context, Result};
+use elements::AddressParams;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable pointing at a config.env file.
+pub const CONFIG_PATH_ENV: &str = "PUZZLE_CONFIG";
+
+/// Config keys read from config.env and the environment.
+const CONFIG_KEYS: &[&str] = &[
+    "ELEMENTS_CLI_PATH",
+    "ELEMENTS_CHAIN",
+    "WALLET_NAME",
+    "ELEMENTS_DATA_DIR",
+    "RPC_USER",
+    "RPC_PASSWORD",
+    "RPC_PORT",
+];
+
+/// Where to look for elements-cli when ELEMENTS_CLI_PATH is not set,
+/// mirroring the ./elements-cli wrapper script.
+const DEFAULT_CLI_PATHS: &[&str] = &[
+    "~/elements/src/elements-cli",
+    "/usr/local/bin/elements-cli",
+];
+
+pub struct ElementsCli {
+    cli_path: PathBuf,
+    chain: String,
+    wallet: String,
+    extra_args: Vec<String>,
+}
+
+impl ElementsCli {
+    /// Build from config.env and environment variables.
+    pub fn from_config() -> Result<Self> {
+        let config = load_config()?;
+
+        let cli_path = match config.get("ELEMENTS_CLI_PATH") {
+            Some(path) => expand_home(path),
+            None => find_default_cli(),
+        };
+
+        let mut extra_args = vec![];
+        if let Some(dir) = config.get("ELEMENTS_DATA_DIR") {
+            extra_args.push(format!("-datadir={}", expand_home(dir).display()));
+        }
+        if let Some(user) = config.get("RPC_USER") {
+            extra_args.push(format!("-rpcuser={}", user));
+        }
+        if let Some(password) = config.get("RPC_PASSWORD") {
+            extra_args.push(format!("-rpcpassword={}", password));
+        }
+        if let Some(port) = config.get("RPC_PORT") {
+            extra_args.push(format!("-rpcport={}", port));
+        }
+
+        Ok(ElementsCli {
+            cli_path,
+            chain: config.get("ELEMENTS_CHAIN").cloned().unwrap_or_else(|| "liquidtestnet".to_string()),
+            wallet: config.get("WALLET_NAME").cloned().unwrap_or_else(|| "my_wallet".to_string()),
+            extra_args,
+        })
+    }
+
+    /// Chain name passed as -chain=.
+    pub fn chain(&self) -> &str {
+        &self.chain
+    }
+
+    /// Wallet used by `call_wallet`.
+    pub fn wallet(&self) -> &str {
+        &self.wallet
+    }
+
+    /// Address parameters matching the configured chain.
+    pub fn address_params(&self) -> &'static AddressParams {
+        address_params_for(&self.chain)
+    }
+
+    fn command(&self, wallet: bool) -> Command {
+        let mut command = Command::new(&self.cli_path);
+        command.arg(format!("-chain={}", self.chain));
+        command.args(&self.extra_args);
+        if wallet {
+            command.arg(format!("-rpcwallet={}", self.wallet));
+        }
+        command
+    }
+
+    fn run(&self, mut command: Command, args: &[&str]) -> Result<String> {
+        let output = command
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to execute {}", self.cli_path.display()))?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("{} failed: {}", args.first().unwrap_or(&""), error.trim()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Run an RPC and return its trimmed stdout.
+    pub fn call(&self, args: &[&str]) -> Result<String> {
+        self.run(self.command(false), args)
+    }
+
+    /// Run an RPC against the configured wallet.
+    pub fn call_wallet(&self, args: &[&str]) -> Result<String> {
+        self.run(self.command(true), args)
+    }
+
+    /// Run an RPC and parse its output as JSON.
+    pub fn call_json(&self, args: &[&str]) -> Result<serde_json::Value> {
+        let output = self.call(args)?;
+        if output.is_empty() {
+            return Ok(serde_json::Value::Null);
+        }
+        serde_json::from_str(&output)
+            .with_context(|| format!("Failed to parse {} output", args.first().unwrap_or(&"")))
+    }
+
+    /// Run a wallet RPC and parse its output as JSON.
+    pub fn call_wallet_json(&self, args: &[&str]) -> Result<serde_json::Value> {
+        let output = self.call_wallet(args)?;
+        serde_json::from_str(&output)
+            .with_context(|| format!("Failed to parse {} output", args.first().unwrap_or(&"")))
+    }
+
+    /// Whether the node answers at all.
+    pub fn is_available(&self) -> bool {
+        self.call(&["getblockcount"]).is_ok()
+    }
+
+    /// `gettxout`, including the mempool. `None` means spent or unknown.
+    pub fn get_tx_out(&self, txid: &str, vout: u32) -> Result<Option<serde_json::Value>> {
+        let utxo = self.call_json(&["gettxout", txid, &vout.to_string(), "true"])?;
+        Ok(if utxo.is_null() { None } else { Some(utxo) })
+    }
+
+    /// Verbose `getrawtransaction`.
+    pub fn get_raw_transaction(&self, txid: &str) -> Result<serde_json::Value> {
+        self.call_json(&["getrawtransaction", txid, "true"])
+    }
+}
+
+/// Address parameters for an Elements chain name.
+pub fn address_params_for(chain: &str) -> &'static AddressParams {
+    match chain {
+        "liquidv1" => &AddressParams::LIQUID,
+        "elementsregtest" | "regtest" => &AddressParams::ELEMENTS,
+        _ => &AddressParams::LIQUID_TESTNET,
+    }
+}
+
+/// Read config.env (if present), then let environment variables override it.
+fn load_config() -> Result<HashMap<String, String>> {
+    let path = std::env::var(CONFIG_PATH_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("config.env"));
+
+    let mut config = HashMap::new();
+    if path.exists() {
+        config.extend(parse_env_file(&path)?);
+    }
+
+    for key in CONFIG_KEYS {
+        if let Ok(value) = std::env::var(key) {
+            config.insert(key.to_string(), value);
+        }
+    }
+
+    Ok(config)
+}
+
+/// Parse KEY=VALUE lines, skipping comments and stripping quotes.
+fn parse_env_file(path: &Path) -> Result<HashMap<String, String>> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    Ok(data
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path.replace("$HOME", &std::env::var("HOME").unwrap_or_default())),
+    }
+}
+
+fn find_default_cli() -> PathBuf {
+    DEFAULT_CLI_PATHS
+        .iter()
+        .map(|path| expand_home(path))
+        .find(|path| path.exists())
+        // Fall back to whatever elements-cli is on PATH
+        .unwrap_or_else(|| PathBuf::from("elements-cli"))
+}
@@ -0,0 +1,90 @@
+/*
+ * ADD TO POT - Send more funds to an existing puzzle
+ *
+ * Usage:
+ *   puzzle-hunt add-to-pot --puzzle <puzzle_file.json> --amount <amount>
+ *
+ * Example:
+ *   puzzle-hunt add-to-pot --puzzle puzzle_2cf24dba.json --amount 0.05
+ *
+ * The puzzle address is recomputed from the target hash first, so funds
+ * never go to an address that is not the puzzle contract. Confidential
+ * puzzles are funded at their confidential address, with the same
+ * blinding key.
+ *
+ * Each addition is its own output of the puzzle address; the winner
+ * claims it with: puzzle-hunt solve --outpoint <txid:vout> ...
+ * It is recorded as a pot addition in the puzzle database.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use crate::contract::{self, PuzzleContract};
+use crate::funding;
+use crate::output::{self, ErrorCode};
+use crate::progress;
+use crate::store::PuzzleStore;
+use anyhow::{Context, Result};
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct AddToPotArgs {
+    /// Public puzzle file (puzzle_XXXX.json)
+    #[arg(long)]
+    pub puzzle: String,
+    /// Amount to add, e.g. 0.05 or "5000000 sat"
+    #[arg(long)]
+    pub amount: Amount,
+}
+
+pub fn run(args: AddToPotArgs) -> Result<serde_json::Value> {
+    let puzzle: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&args.puzzle).context("Failed to read puzzle file")?,
+    )
+    .context("Failed to parse puzzle JSON")?;
+    let target_hash = puzzle["target_hash"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing target_hash in puzzle file"))?;
+    let address = puzzle["address"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing address in puzzle file"))?;
+
+    let cli = ElementsCli::from_config()?;
+    let contract = PuzzleContract::compile(contract::parse_target_hash(target_hash)?)?;
+    let expected = contract.address(None, cli.address_params());
+    if expected.to_string() != address {
+        return Err(output::error(
+            ErrorCode::ContractMismatch,
+            format!("❌ Address {} is not the puzzle contract for {}; not sending funds", address, target_hash),
+        ));
+    }
+    let funding_address = puzzle["confidential_address"].as_str().unwrap_or(address);
+
+    progress!("💰 Adding {} L-BTC to puzzle {}", args.amount, target_hash);
+    progress!("   📍 Address: {}", funding_address);
+    let txid = funding::fund_puzzle(&cli, funding_address, args.amount)
+        .map_err(|e| anyhow::anyhow!("Failed to send funds: {}", e))?;
+    let output = funding::locate_output(&cli, &txid, &expected.script_pubkey())
+        .with_context(|| format!("Funds were sent in {} but the puzzle output could not be identified", txid))?;
+    progress!("✅ Pot increased: {}:{}", txid, output.vout);
+
+    // The funds are sent, so a database error is only a warning
+    let recorded = PuzzleStore::open_default().and_then(|db| {
+        let Some(record) = db.find_by_hash(target_hash)? else {
+            return Ok(false);
+        };
+        db.add_pot_addition(record.id, &txid, output.vout, Some(args.amount))?;
+        Ok(true)
+    });
+    match recorded {
+        Ok(true) => {}
+        Ok(false) => eprintln!("⚠️  Puzzle not in {}; import it with puzzle-db import", PuzzleStore::default_path().display()),
+        Err(e) => eprintln!("⚠️  Could not record the pot addition: {}", e),
+    }
+
+    progress!("   Claim it with: puzzle-hunt solve --outpoint {}:{} --puzzle {} ...", txid, output.vout, args.puzzle);
+    Ok(serde_json::json!({
+        "address": address,
+        "txid": txid,
+        "vout": output.vout,
+        "amount_sats": args.amount,
+    }))
+}
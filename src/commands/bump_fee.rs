@@ -0,0 +1,116 @@
+/*
+ * BUMP FEE - Replace a pending puzzle spend with a higher-fee version
+ *
+ * Usage:
+ *   puzzle-hunt bump-fee --spend <spend_txid | puzzle_file.json> [--fee-rate <sat/vB> | --fee <sats>]
+ *
+ * Examples:
+ *   puzzle-hunt bump-fee --spend puzzle_2cf24dba.json
+ *   puzzle-hunt bump-fee --spend 5e1f...c0de --fee-rate 2
+ *
+ * solve saves every signed spend in the puzzle database. This rebuilds
+ * the latest one with a higher fee, taken from the prize, and
+ * broadcasts it as an RBF replacement. If another spend of the puzzle is
+ * in the mempool, the new fee is raised to outbid it.
+ *
+ * Without --fee-rate or --fee the rate comes from estimatesmartfee.
+ * For automatic bumping, run: puzzle-hunt watch --auto-bump
+ */
+
+use crate::chain::ElementsCli;
+use crate::fees::{self, FeePolicy};
+use crate::progress;
+use crate::rbf;
+use crate::solves;
+use crate::store::PuzzleStore;
+use anyhow::{Context, Result};
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct BumpFeeArgs {
+    /// Txid of the saved spend, or the puzzle file to bump its latest spend
+    #[arg(long, value_name = "TXID|PUZZLE_FILE")]
+    pub spend: String,
+    /// Fee rate in sat/vB [default: estimatesmartfee]
+    #[arg(long, conflicts_with = "fee")]
+    pub fee_rate: Option<String>,
+    /// Absolute fee in sats
+    #[arg(long)]
+    pub fee: Option<String>,
+}
+
+pub fn run(args: BumpFeeArgs) -> Result<serde_json::Value> {
+    let policy = FeePolicy::from_args(args.fee_rate.as_deref(), args.fee.as_deref())?;
+    let target = &args.spend;
+
+    let cli = ElementsCli::from_config()?;
+    let db = PuzzleStore::open_default()?;
+
+    let spend = if target.ends_with(".json") {
+        let data = std::fs::read_to_string(target).with_context(|| format!("Failed to read {}", target))?;
+        let puzzle: serde_json::Value = serde_json::from_str(&data)?;
+        let txid = puzzle["txid"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing txid in puzzle file"))?;
+        let vout = puzzle["vout"].as_u64()
+            .ok_or_else(|| anyhow::anyhow!("Missing vout in puzzle file"))? as u32;
+        db.latest_spend(txid, vout)?
+    } else {
+        db.spend(target)?
+    };
+    let Some(spend) = spend else {
+        return Err(anyhow::anyhow!("❌ No saved spend for {}; solve records spends when it broadcasts", target));
+    };
+
+    progress!("⚡ Bumping spend {}", spend.txid);
+    progress!("   Puzzle outpoint: {}:{}", spend.funding_txid, spend.funding_vout);
+    progress!(
+        "   Current fee: {} sats ({:.2} sat/vB, {} vB)",
+        spend.fee.to_sat(),
+        fees::effective_rate(spend.fee, spend.vsize),
+        spend.vsize
+    );
+
+    // What currently spends the puzzle, if anything
+    let current = solves::find_solve(&cli, &db, &spend.funding_txid, spend.funding_vout, "")?;
+    if let Some(current) = &current {
+        if current.block_height.is_some() {
+            return Err(anyhow::anyhow!("❌ Puzzle already spent by confirmed transaction {}", current.txid));
+        }
+    }
+
+    let mut new_fee = match policy {
+        FeePolicy::Fixed(fee) => fee,
+        FeePolicy::Rate(rate) => rbf::replacement_fee(spend.fee, spend.vsize, rate),
+        FeePolicy::Estimate { conf_target } => {
+            let rate = fees::estimate_rate(&cli, conf_target)?.unwrap_or(fees::MIN_RELAY_FEE_RATE);
+            rbf::replacement_fee(spend.fee, spend.vsize, rate)
+        }
+    };
+
+    if let Some(current) = current {
+        if !db.is_own_spend(&spend.funding_txid, spend.funding_vout, &current.txid)? {
+            let outbid = rbf::outbid_fee(&cli, &current.txid, spend.vsize)?;
+            progress!("   🏁 Competing spend in mempool: {} (need at least {} sats)", current.txid, outbid.to_sat());
+            new_fee = new_fee.max(outbid);
+        }
+    }
+
+    if new_fee <= spend.fee {
+        return Err(anyhow::anyhow!(
+            "❌ New fee ({} sats) must be higher than the current fee ({} sats)",
+            new_fee.to_sat(),
+            spend.fee.to_sat()
+        ));
+    }
+
+    progress!("   New fee: {} sats ({:.2} sat/vB)", new_fee.to_sat(), fees::effective_rate(new_fee, spend.vsize));
+    let txid = rbf::rebroadcast(&cli, &db, &spend, new_fee)?;
+
+    progress!("✅ Replacement broadcast: {}", txid);
+    Ok(serde_json::json!({
+        "txid": txid,
+        "replaces": spend.txid,
+        "fee_sats": new_fee,
+        "fee_rate": fees::effective_rate(new_fee, spend.vsize),
+    }))
+}
@@ -0,0 +1,551 @@
+/*
+ * CREATE - Create and fund a puzzle
+ *
+ * Usage:
+ *   puzzle-hunt create --secret <secret> --amount <amount> [--hint <hint>]
+ *                      [--confidential | --confidential-separate]
+ *                      [--hint-at <height|time>=<hint> ...]
+ *                      [--contract puzzle_jackpot]
+ *   puzzle-hunt create --batch <campaign.yaml | campaign.csv>
+ *
+ * Examples:
+ *   puzzle-hunt create --secret "satoshi" --amount 0.1
+ *   puzzle-hunt create --secret "bitcoin" --amount 0.5 --hint "The creator of Bitcoin"
+ *   puzzle-hunt create --secret "sats" --amount "50000 sat"
+ *
+ * The amount is L-BTC with at most 8 decimals, or satoshis with a
+ * "sat" suffix.
+ *
+ * This will:
+ * 1. Calculate the SHA256 of the secret
+ * 2. Create a Simplicity contract with that hash
+ * 3. Fund it with the specified amount as an explicit (unblinded) output
+ * 4. Find the puzzle output (vout) in the funding transaction
+ * 5. Save puzzle information with hint
+ * 6. Print the address and puzzle details
+ *
+ * The hint is optional. If not provided, it defaults to showing the
+ * character count of the secret.
+ *
+ * --hint-at schedules more hints, released at a block height or an
+ * RFC 3339 time (e.g. --hint-at "1200=It is a name"). The schedule is
+ * kept in the encrypted SECRET file; the public file only gets the hints
+ * published so far by release-hints (see hints.rs).
+ *
+ * With --confidential the puzzle output is blinded and the blinding key
+ * is published in the public puzzle file so participants can unblind it.
+ * With --confidential-separate the key is written to its own
+ * puzzle_XXXX_BLINDING.key file instead, to hand out separately.
+ *
+ * The _SECRET.json file is encrypted with age. Set
+ * PUZZLE_SECRET_RECIPIENT=age1... to encrypt to a key, or
+ * PUZZLE_SECRET_PASSPHRASE to use a passphrase without being prompted.
+ * Read it back with: cargo run --bin reveal-secret -- <secret_file>
+ *
+ * The JSON result has the address, txid, vout, CMR and file names.
+ *
+ * With --batch every puzzle of a campaign file is compiled first, then
+ * all are funded in one transaction with one output each, and the
+ * campaign manifest records which vout belongs to which puzzle (see
+ * campaign.rs). The SECRET files share one encryption key.
+ *
+ * Campaign puzzles can have locked hints, encrypted under the secret of
+ * another puzzle of the campaign (see locked_hints.rs).
+ */
+
+use crate::amount::Amount;
+use crate::budget::{self, BudgetReport};
+use crate::campaign::{self, CampaignPuzzle, Manifest, ManifestEntry};
+use crate::chain::ElementsCli;
+use crate::confidential;
+use crate::contract::{self, PuzzleContract};
+use crate::funding;
+use crate::hints::ScheduledHint;
+use crate::locked_hints::{self, LockedHint};
+use crate::progress;
+use crate::secret_file::{self, SecretKey};
+use crate::store::{self, NewPuzzle, PuzzleStore};
+use anyhow::Result;
+use clap::Args;
+use elements::secp256k1_zkp as secp256k1;
+use elements::Address;
+use secp256k1::{PublicKey, SecretKey as BlindingKey};
+use std::path::Path;
+
+#[derive(Debug, Args)]
+pub struct CreateArgs {
+    /// The secret participants have to find (case-sensitive)
+    #[arg(long, required_unless_present = "batch", conflicts_with = "batch")]
+    pub secret: Option<String>,
+    /// Prize, e.g. 0.1, "0.1 L-BTC" or "50000 sat"
+    #[arg(long, required_unless_present = "batch", conflicts_with = "batch")]
+    pub amount: Option<Amount>,
+    /// Hint for participants [default: the secret's length]
+    #[arg(long)]
+    pub hint: Option<String>,
+    /// Hint released later: <height>=<hint> or <RFC 3339 time>=<hint> (repeatable)
+    #[arg(long, value_name = "WHEN=HINT")]
+    pub hint_at: Vec<ScheduledHint>,
+    /// Blind the prize and publish the blinding key in the puzzle file
+    #[arg(long, conflicts_with = "confidential_separate")]
+    pub confidential: bool,
+    /// Blind the prize and write the blinding key to its own file
+    #[arg(long)]
+    pub confidential_separate: bool,
+    /// Contract to create
+    #[arg(long, value_parser = contract::CONTRACTS, default_value = campaign::DEFAULT_CONTRACT, conflicts_with = "batch")]
+    pub contract: String,
+    /// Create every puzzle of a campaign file (YAML or CSV) in one funding transaction
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["hint", "hint_at", "confidential", "confidential_separate"])]
+    pub batch: Option<String>,
+}
+
+/// A compiled puzzle whose address is known, before it is funded.
+struct Prepared {
+    contract: String,
+    secret: String,
+    amount: Amount,
+    hint: String,
+    hint_schedule: Vec<ScheduledHint>,
+    locked_hints: Vec<LockedHint>,
+    hash_hex: String,
+    cmr: String,
+    budget: BudgetReport,
+    blinding: Option<(BlindingKey, PublicKey)>,
+    publish_blinding_key: bool,
+    funding_address: Address,
+    address: Address,
+}
+
+impl Prepared {
+    fn blinding_key_hex(&self) -> Option<String> {
+        self.blinding.map(|(sk, _)| hex::encode(sk.secret_bytes()))
+    }
+
+    fn public_filename(&self) -> String {
+        format!("puzzle_{}.json", &self.hash_hex[..8])
+    }
+
+    fn private_filename(&self) -> String {
+        format!("puzzle_{}_SECRET.json", &self.hash_hex[..8])
+    }
+
+    fn blinding_filename(&self) -> Option<String> {
+        (self.blinding.is_some() && !self.publish_blinding_key)
+            .then(|| format!("puzzle_{}_BLINDING.key", &self.hash_hex[..8]))
+    }
+}
+
+pub fn run(args: CreateArgs) -> Result<serde_json::Value> {
+    if let Some(batch) = &args.batch {
+        return run_batch(Path::new(batch));
+    }
+    let spec = CampaignPuzzle {
+        secret: args.secret.expect("clap requires --secret"),
+        amount: args.amount.expect("clap requires --amount"),
+        hint: args.hint,
+        hints: args.hint_at,
+        locked_hints: vec![],
+        contract: Some(args.contract),
+        confidential: Some(args.confidential),
+    };
+
+    progress!("╔══════════════════════════════════════╗");
+    progress!("║       🎯 CREATING PUZZLE HUNT 🎯     ║");
+    progress!("╚══════════════════════════════════════╝");
+    progress!();
+
+    let cli = ElementsCli::from_config()?;
+    let prepared = prepare(&cli, &spec, args.confidential_separate)?;
+
+    // Ask for the SECRET file key before any funds move
+    let secret_key = secret_file::encryption_key_from_env()?;
+    progress!("🔑 SECRET file will be encrypted with {}", secret_key.describe());
+    progress!();
+
+    // 4. Send funds using elements-cli
+    progress!("💰 Funding puzzle with {} L-BTC...", prepared.amount);
+
+    // NOTE: elementsd must be running! Check with: ps aux | grep elementsd
+    // The puzzle output is explicit (unblinded) unless --confidential was given
+    let txid = funding::fund_puzzle(&cli, &prepared.funding_address.to_string(), prepared.amount)
+        .map_err(|e| anyhow::anyhow!("Failed to send funds: {}", e))?;
+    progress!("✅ Puzzle funded!");
+    progress!("   TXID: {}", txid);
+    progress!();
+
+    let result = complete(&cli, &prepared, &txid, &secret_key)?;
+    let filename = prepared.public_filename();
+    let private_filename = prepared.private_filename();
+
+    progress!("╔══════════════════════════════════════╗");
+    progress!("║    🎉 PUZZLE CREATED SUCCESSFULLY!    ║");
+    progress!("╚══════════════════════════════════════╝");
+    progress!();
+    progress!("📢 Share with participants:");
+    progress!("   📍 Address: {}", prepared.address);
+    progress!("   💰 Prize: {} L-BTC", prepared.amount);
+    progress!("   💡 Hint: \"{}\"", prepared.hint);
+    progress!("   🔐 Target Hash: 0x{}", prepared.hash_hex);
+    progress!("   📄 Puzzle file: {}", filename);
+    progress!();
+    progress!("⚠️  IMPORTANT:");
+    progress!("   - DO NOT share the _SECRET.json file!");
+    progress!("   - Decrypt it with: cargo run --bin reveal-secret -- {}", private_filename);
+    progress!("   - The secret is case-sensitive");
+    progress!("   - Share the {} file with participants", filename);
+    if let Some(blinding_filename) = prepared.blinding_filename() {
+        progress!("   - Give participants the blinding key in {} to unblind the prize", blinding_filename);
+    }
+
+    Ok(result)
+}
+
+/// Create and fund every puzzle of a campaign file in one transaction.
+fn run_batch(path: &Path) -> Result<serde_json::Value> {
+    let campaign = campaign::load(path)?;
+    let name = campaign.name.clone().unwrap_or_else(|| path.display().to_string());
+
+    progress!("╔══════════════════════════════════════╗");
+    progress!("║     🎯 CREATING PUZZLE CAMPAIGN 🎯    ║");
+    progress!("╚══════════════════════════════════════╝");
+    progress!();
+    progress!("📋 Campaign {}: {} puzzles", name, campaign.puzzles.len());
+    progress!();
+
+    // Compile everything before any funds move
+    let cli = ElementsCli::from_config()?;
+    let mut prepared = Vec::with_capacity(campaign.puzzles.len());
+    for (i, spec) in campaign.puzzles.iter().enumerate() {
+        progress!("── Puzzle {}/{} ──", i + 1, campaign.puzzles.len());
+        let puzzle = prepare(&cli, spec, false).map_err(|e| anyhow::anyhow!("Puzzle {}: {}", i + 1, e))?;
+        // Secrets longer than 32 bytes are truncated, so two can share a hash
+        if prepared.iter().any(|p: &Prepared| p.hash_hex == puzzle.hash_hex) {
+            return Err(anyhow::anyhow!("Puzzle {}: same target hash as an earlier puzzle (0x{})", i + 1, puzzle.hash_hex));
+        }
+        prepared.push(puzzle);
+    }
+
+    // Locked hints need every puzzle's secret and target hash
+    let secrets: Vec<String> = prepared.iter().map(|p| p.secret.clone()).collect();
+    let target_hashes: Vec<String> = prepared.iter().map(|p| format!("0x{}", p.hash_hex)).collect();
+    for (i, (puzzle, spec)) in prepared.iter_mut().zip(&campaign.puzzles).enumerate() {
+        if spec.locked_hints.is_empty() {
+            continue;
+        }
+        progress!("🔒 Locking {} hints of puzzle {}...", spec.locked_hints.len(), i + 1);
+        puzzle.locked_hints = locked_hints::lock(&spec.locked_hints, &secrets, &target_hashes)
+            .map_err(|e| anyhow::anyhow!("Puzzle {}: {}", i + 1, e))?;
+    }
+    progress!();
+
+    let secret_key = secret_file::encryption_key_from_env()?;
+    progress!("🔑 SECRET files will be encrypted with {}", secret_key.describe());
+    progress!();
+
+    let payments: Vec<(String, Amount)> = prepared
+        .iter()
+        .map(|p| (p.funding_address.to_string(), p.amount))
+        .collect();
+    let total: Amount = prepared.iter().map(|p| p.amount).sum();
+    progress!("💰 Funding {} puzzles with {} L-BTC in one transaction...", prepared.len(), total);
+    let txid = funding::fund_puzzles(&cli, &payments)
+        .map_err(|e| anyhow::anyhow!("Failed to send funds: {}", e))?;
+    progress!("✅ Campaign funded!");
+    progress!("   TXID: {}", txid);
+    progress!();
+
+    // The funds are sent: keep going past a puzzle that cannot be saved,
+    // so the others still get their files
+    let mut entries = Vec::new();
+    let mut failed = Vec::new();
+    for puzzle in &prepared {
+        match complete(&cli, puzzle, &txid, &secret_key) {
+            Ok(result) => entries.push(ManifestEntry {
+                target_hash: format!("0x{}", puzzle.hash_hex),
+                contract: puzzle.contract.clone(),
+                address: puzzle.address.to_string(),
+                vout: result["vout"].as_u64().unwrap_or_default() as u32,
+                amount_sats: puzzle.amount,
+                cmr: puzzle.cmr.clone(),
+                public_file: puzzle.public_filename(),
+                secret_file: puzzle.private_filename(),
+                locked_hints: puzzle.locked_hints.iter().map(|h| h.unlock.clone()).collect(),
+            }),
+            Err(e) => {
+                eprintln!("❌ Puzzle 0x{}: {}", puzzle.hash_hex, e);
+                failed.push(format!("0x{}", puzzle.hash_hex));
+            }
+        }
+    }
+
+    let manifest = Manifest {
+        name: campaign.name,
+        network: cli.chain().to_string(),
+        funding_txid: txid.clone(),
+        total_sats: total,
+        created_at: chrono::Local::now().to_rfc3339(),
+        puzzles: entries,
+    };
+    let manifest_file = campaign::manifest_path(path);
+    manifest.write(&manifest_file)?;
+
+    progress!("╔══════════════════════════════════════╗");
+    progress!("║   🎉 CAMPAIGN CREATED SUCCESSFULLY!   ║");
+    progress!("╚══════════════════════════════════════╝");
+    progress!();
+    for entry in &manifest.puzzles {
+        progress!("   vout {:>3}  {}  {} L-BTC  {}", entry.vout, &entry.target_hash[..10], entry.amount_sats, entry.public_file);
+    }
+    progress!();
+    progress!("🗺️  Manifest: {}", manifest_file.display());
+    progress!("⚠️  DO NOT share the _SECRET.json files!");
+
+    if !failed.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} puzzles were funded in {} but not saved: {}",
+            failed.len(),
+            txid,
+            failed.join(", ")
+        ));
+    }
+
+    Ok(serde_json::json!({
+        "manifest": manifest_file.display().to_string(),
+        "funding_txid": txid,
+        "total_sats": total,
+        "puzzles": manifest.puzzles,
+    }))
+}
+
+/// Hash the secret, compile the contract, check its cost budget and
+/// derive the puzzle address. Nothing is sent.
+fn prepare(cli: &ElementsCli, spec: &CampaignPuzzle, confidential_separate: bool) -> Result<Prepared> {
+    let publish_blinding_key = spec.confidential.unwrap_or(false);
+    let confidential_mode = publish_blinding_key || confidential_separate;
+
+    let secret = &spec.secret;
+    let amount = spec.amount;
+    let hint = spec
+        .hint
+        .clone()
+        .unwrap_or_else(|| format!("The secret has {} characters", secret.len()));
+
+    // 1. Calculate hash of the secret
+    progress!("📋 Puzzle Configuration:");
+    progress!("   📜 Contract: {}", spec.contract());
+    progress!("   📝 Secret: {}", secret);
+    progress!("   💰 Amount: {} L-BTC", amount);
+    progress!("   💡 Hint: \"{}\"", hint);
+    for scheduled in &spec.hints {
+        progress!("   ⏳ At {}: \"{}\"", scheduled.at, scheduled.hint);
+    }
+    progress!();
+
+    progress!("🔐 Processing secret and value...");
+    let hash = contract::hash_secret(&contract::text_secret(secret));
+    let hash_hex = hex::encode(hash);
+    progress!("✅ Target Hash computed: 0x{}", hash_hex);
+    progress!("   Formula: SHA256(secret)");
+    progress!();
+
+    // 2. Compile the contract with the hash
+    progress!("⚙️  Compiling Simplicity contract...");
+    let contract = PuzzleContract::compile(hash)?;
+    progress!("✅ Contract compiled!");
+    progress!();
+
+    // Cost versus witness weight, with a placeholder secret of the same size
+    progress!("🧮 Cost budget (placeholder secret):");
+    let satisfied = contract.satisfy([0u8; 32])?;
+    let (program_bytes, witness_bytes) = satisfied.redeem().to_vec_with_witness();
+    let budget_report = budget::report(
+        satisfied.redeem(),
+        &[witness_bytes, program_bytes, contract.script.as_bytes().to_vec(), contract.control_block().serialize()],
+    );
+    budget_report.print();
+    if !budget_report.consensus_valid {
+        return Err(anyhow::anyhow!("Contract cost exceeds the consensus maximum; it could never be solved"));
+    }
+    if budget_report.padding_needed.is_some() {
+        progress!("   solve-puzzle adds the annex automatically");
+    }
+    progress!();
+
+    let blinding = if confidential_mode {
+        Some(confidential::generate_blinding_key()?)
+    } else {
+        None
+    };
+
+    // 3. Create Taproot address
+    let funding_address = contract.address(blinding.map(|(_, pk)| pk), cli.address_params());
+    // The node reports outputs by their unconfidential address, so that is
+    // what the public file, database and watcher use
+    let address = funding_address.to_unconfidential();
+
+    progress!("📍 Puzzle Address:");
+    progress!("   {}", address);
+    if blinding.is_some() {
+        progress!("   🙈 Confidential: {}", funding_address);
+    }
+    progress!();
+
+    Ok(Prepared {
+        contract: spec.contract().to_string(),
+        secret: secret.clone(),
+        amount,
+        hint,
+        hint_schedule: spec.hints.clone(),
+        locked_hints: vec![],
+        hash_hex,
+        cmr: hex::encode(contract.script.as_bytes()),
+        budget: budget_report,
+        blinding,
+        publish_blinding_key,
+        funding_address,
+        address,
+    })
+}
+
+/// Save the files of a funded puzzle and record it in the database.
+fn complete(cli: &ElementsCli, prepared: &Prepared, txid: &str, secret_key: &SecretKey) -> Result<serde_json::Value> {
+    let Prepared { secret, amount, hint, hash_hex, blinding, publish_blinding_key, address, funding_address, .. } = prepared;
+    let amount = *amount;
+    let blinding_key_hex = prepared.blinding_key_hex();
+
+    // Save private info for creator only
+    let private_info = serde_json::json!({
+        "secret": secret,
+        "hash": format!("0x{}", hash_hex),
+        "txid": txid,
+        "amount": amount.to_string(),
+        "amount_sats": amount,
+        "hint": hint.clone(),
+        "hint_schedule": prepared.hint_schedule,
+        "address": address.to_string(),
+        "blinding_key": blinding_key_hex,
+        "created_at": chrono::Local::now().to_rfc3339(),
+    });
+
+    let private_filename = prepared.private_filename();
+    secret_file::write_encrypted(Path::new(&private_filename), &private_info, secret_key)?;
+
+    // 5. Find the puzzle output among the wallet's change outputs
+    progress!("🔎 Locating puzzle output in funding transaction...");
+    let funding = funding::locate_output(cli, txid, &address.script_pubkey()).map_err(|e| {
+        anyhow::anyhow!(
+            "{}\n   Funds were sent in {} but the puzzle output could not be identified.\n   The secret was saved to {}.",
+            e, txid, private_filename
+        )
+    })?;
+    progress!("✅ Puzzle output is vout {}", funding.vout);
+    let asset = match blinding {
+        Some((blinding_key, _)) => {
+            let blinding_key = *blinding_key;
+            // Check that participants will be able to unblind it
+            let (_, secrets) = confidential::unblind_output(cli, txid, funding.vout, blinding_key)?;
+            progress!("   🙈 Blinded value: {} sats", secrets.value);
+            progress!("   🪙 Asset: {}", secrets.asset);
+            secrets.asset.to_string()
+        }
+        None => {
+            let (onchain_value, asset) = funding.require_explicit()?;
+            progress!("   💰 Explicit value: {} L-BTC", onchain_value);
+            progress!("   🪙 Asset: {}", asset);
+            asset.to_string()
+        }
+    };
+    progress!();
+
+    // 6. Save puzzle information
+    let public_info = serde_json::json!({
+        "target_hash": format!("0x{}", hash_hex),
+        "contract": prepared.contract,
+        "address": address.to_string(),
+        "network": cli.chain(),
+        "txid": txid,
+        "vout": funding.vout,
+        "amount": amount.to_string(),
+        "amount_sats": amount,
+        "asset": asset,
+        "confidential": blinding.is_some(),
+        "confidential_address": blinding.map(|_| funding_address.to_string()),
+        "blinding_key": if *publish_blinding_key { blinding_key_hex.clone() } else { None },
+        "value_commitment": funding.value_commitment,
+        "asset_commitment": funding.asset_commitment,
+        "hint": hint.clone(),
+        "hints": [],
+        "pending_hints": prepared.hint_schedule.len(),
+        "locked_hints": prepared.locked_hints,
+        "budget": prepared.budget,
+        "created_at": chrono::Local::now().to_rfc3339(),
+    });
+
+    let filename = prepared.public_filename();
+    std::fs::write(&filename, serde_json::to_string_pretty(&public_info)?)?;
+
+    let blinding_filename = prepared.blinding_filename();
+    if let (Some(key), Some(blinding_filename)) = (&blinding_key_hex, &blinding_filename) {
+        std::fs::write(blinding_filename, format!("{}\n", key))?;
+    }
+
+    // 7. Record the puzzle in the local database
+    // The puzzle is already funded, so a database error is only a warning
+    if let Err(e) = record_puzzle(&public_info, cli.chain(), &filename, &private_filename) {
+        eprintln!("⚠️  Could not record puzzle in {}: {}", PuzzleStore::default_path().display(), e);
+        eprintln!("   Import it later with: cargo run --bin puzzle-db -- import {}", filename);
+    }
+
+    progress!("💾 Files saved:");
+    progress!("   📄 Public file: {}", filename);
+    progress!("   🔒 Private file: {} (encrypted)", private_filename);
+    if let Some(blinding_filename) = &blinding_filename {
+        progress!("   🙈 Blinding key: {}", blinding_filename);
+    }
+    progress!();
+
+    Ok(serde_json::json!({
+        "address": public_info["address"],
+        "confidential_address": public_info["confidential_address"],
+        "txid": txid,
+        "vout": funding.vout,
+        "cmr": prepared.cmr,
+        "target_hash": public_info["target_hash"],
+        "amount_sats": amount,
+        "asset": public_info["asset"],
+        "public_file": filename,
+        "secret_file": private_filename,
+        "blinding_file": blinding_filename,
+        "budget": public_info["budget"],
+    }))
+}
+
+fn record_puzzle(
+    public_info: &serde_json::Value,
+    network: &str,
+    filename: &str,
+    private_filename: &str,
+) -> Result<()> {
+    let db = PuzzleStore::open_default()?;
+    let amount = public_info["amount"].as_str().and_then(|a| a.parse().ok());
+
+    let (id, _) = db.insert_puzzle(&NewPuzzle {
+        target_hash: public_info["target_hash"].as_str().unwrap_or_default().to_string(),
+        address: public_info["address"].as_str().unwrap_or_default().to_string(),
+        network: network.to_string(),
+        contract: public_info["contract"].as_str().unwrap_or(campaign::DEFAULT_CONTRACT).to_string(),
+        amount_sats: amount,
+        hint: public_info["hint"].as_str().map(str::to_string),
+        created_at: public_info["created_at"].as_str().map(str::to_string),
+        public_file: Some(filename.to_string()),
+        secret_file: Some(private_filename.to_string()),
+    })?;
+
+    let txid = public_info["txid"].as_str().unwrap_or_default();
+    let vout = public_info["vout"].as_u64().unwrap_or(0) as u32;
+    db.add_funding_outpoint(id, txid, vout, amount, public_info["asset"].as_str())?;
+    db.set_status(id, store::STATUS_ACTIVE, Some(&format!("funded by {}", txid)))?;
+
+    Ok(())
+}
@@ -0,0 +1,232 @@
+/*
+ * DB - Manage the local puzzle database
+ *
+ * Usage:
+ *   puzzle-hunt db import [file_or_dir...]
+ *   puzzle-hunt db list
+ *   puzzle-hunt db show <hash_prefix>
+ *   puzzle-hunt db set-status <hash_prefix> <status> [note]
+ *
+ * Examples:
+ *   puzzle-hunt db import
+ *   puzzle-hunt db import archived_puzzles/
+ *   puzzle-hunt --output json db show a0dc65ff
+ *
+ * import with no arguments reads every puzzle_*.json in the current
+ * directory. SECRET files are never imported, only their paths.
+ *
+ * The database is puzzles.db, or the path in PUZZLE_DB.
+ *
+ * --output json prints one JSON object (the puzzles for list, the
+ * puzzle with its outpoints, solve and history for show) and sends the
+ * human-readable lines to stderr.
+ */
+
+use crate::progress;
+use crate::store::{ImportOutcome, PuzzleStore};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Args)]
+pub struct DbArgs {
+    #[command(subcommand)]
+    pub command: DbCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Import puzzle_*.json files [default: the current directory]
+    Import {
+        #[arg(value_name = "FILE_OR_DIR")]
+        targets: Vec<String>,
+    },
+    /// List all puzzles
+    List,
+    /// Show one puzzle with its outpoints, solve and history
+    Show {
+        #[arg(value_name = "HASH_PREFIX")]
+        prefix: String,
+    },
+    /// Change a puzzle's status
+    SetStatus {
+        #[arg(value_name = "HASH_PREFIX")]
+        prefix: String,
+        status: String,
+        note: Option<String>,
+    },
+}
+
+pub fn run(args: DbArgs) -> Result<serde_json::Value> {
+    let store = PuzzleStore::open_default()?;
+
+    match args.command {
+        DbCommand::Import { targets } => import(&store, &targets),
+        DbCommand::List => list(&store),
+        DbCommand::Show { prefix } => show(&store, &prefix),
+        DbCommand::SetStatus { prefix, status, note } => {
+            let puzzle = store.find_by_prefix(&prefix)?
+                .ok_or_else(|| anyhow::anyhow!("No puzzle matches {}", prefix))?;
+            store.set_status(puzzle.id, &status, note.as_deref())?;
+            progress!("✅ {} is now {}", puzzle.target_hash, status);
+            Ok(serde_json::json!({ "target_hash": puzzle.target_hash, "status": status }))
+        }
+    }
+}
+
+fn import(store: &PuzzleStore, targets: &[String]) -> Result<serde_json::Value> {
+    let targets: Vec<PathBuf> = if targets.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        targets.iter().map(PathBuf::from).collect()
+    };
+
+    let mut files = vec![];
+    for target in &targets {
+        if target.is_dir() {
+            files.extend(puzzle_files_in(target)?);
+        } else {
+            files.push(target.clone());
+        }
+    }
+
+    let mut inserted = 0;
+    let mut present = 0;
+    let mut failed = 0;
+
+    for file in &files {
+        match store.import_json_file(file) {
+            Ok(ImportOutcome::Inserted) => {
+                inserted += 1;
+                progress!("✅ Imported {}", file.display());
+            }
+            Ok(ImportOutcome::AlreadyPresent) => {
+                present += 1;
+                progress!("   Already in database: {}", file.display());
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("⚠️  Skipped {}: {}", file.display(), e);
+            }
+        }
+    }
+
+    progress!();
+    progress!("📊 Imported: {}, already present: {}, failed: {}", inserted, present, failed);
+    Ok(serde_json::json!({ "imported": inserted, "already_present": present, "failed": failed }))
+}
+
+/// Public puzzle files in a directory, skipping SECRET files.
+fn puzzle_files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        // Archived files are prefixed with a timestamp
+        if name.contains("puzzle_") && name.ends_with(".json") && !name.ends_with("_SECRET.json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn list(store: &PuzzleStore) -> Result<serde_json::Value> {
+    let puzzles = store.list_puzzles()?;
+
+    if puzzles.is_empty() {
+        progress!("❌ No puzzles in database. Try: puzzle-hunt db import");
+        return Ok(serde_json::json!({ "puzzles": [] }));
+    }
+
+    for puzzle in &puzzles {
+        progress!(
+            "{:<10} {:<20} {:>12} L-BTC  {}",
+            puzzle.status,
+            &puzzle.target_hash[..puzzle.target_hash.len().min(20)],
+            puzzle.amount_sats.map_or("?".to_string(), |a| a.to_string()),
+            puzzle.hint.as_deref().unwrap_or(""),
+        );
+    }
+    progress!();
+    progress!("📊 {} puzzles", puzzles.len());
+    Ok(serde_json::json!({ "puzzles": puzzles }))
+}
+
+fn show(store: &PuzzleStore, prefix: &str) -> Result<serde_json::Value> {
+    let puzzle = store.find_by_prefix(prefix)?
+        .ok_or_else(|| anyhow::anyhow!("No puzzle matches {}", prefix))?;
+
+    progress!("🎯 Puzzle {}", puzzle.target_hash);
+    progress!("   📍 Address: {}", puzzle.address);
+    progress!("   🌐 Network: {}", puzzle.network);
+    progress!("   📜 Contract: {}", puzzle.contract);
+    progress!("   💰 Amount: {} L-BTC", puzzle.amount_sats.map_or("?".to_string(), |a| a.to_string()));
+    progress!("   💡 Hint: \"{}\"", puzzle.hint.as_deref().unwrap_or(""));
+    progress!("   📊 Status: {}", puzzle.status);
+    progress!("   📅 Created: {}", puzzle.created_at.as_deref().unwrap_or("Unknown"));
+    if let Some(file) = &puzzle.public_file {
+        progress!("   📄 Public file: {}", file);
+    }
+    if let Some(file) = &puzzle.secret_file {
+        progress!("   🔒 Secret file: {}", file);
+    }
+
+    progress!();
+    progress!("💰 Funding outpoints:");
+    let outpoints = store.funding_outpoints(puzzle.id)?;
+    for outpoint in &outpoints {
+        let spent = outpoint.spent_by.as_ref()
+            .map(|txid| format!(" (spent by {})", txid))
+            .unwrap_or_default();
+        progress!(
+            "   {}:{}  {} L-BTC{}",
+            outpoint.txid,
+            outpoint.vout,
+            outpoint.amount_sats.map_or("?".to_string(), |a| a.to_string()),
+            spent
+        );
+    }
+
+    let solve = store.solve_for(puzzle.id)?;
+    if let Some(solve) = &solve {
+        progress!();
+        progress!("🏆 Solved by {}", solve.txid);
+        let destination = solve.destination.as_deref().unwrap_or("unknown");
+        match &solve.winner {
+            Some(winner) => progress!("   🏅 Winner: {} ({})", winner, destination),
+            None => progress!("   🏅 Winner: {}", destination),
+        }
+        if let Some(text) = &solve.secret_text {
+            progress!("   🔓 Secret: \"{}\"", text);
+        } else if let Some(hex) = &solve.secret_hex {
+            progress!("   🔓 Secret: 0x{}", hex);
+        }
+        progress!("   ✓ Verified against target hash: {}", if solve.verified { "yes" } else { "no" });
+        match solve.block_height {
+            Some(height) => progress!("   ⛓️  Block: {}", height),
+            None => progress!("   ⏳ Unconfirmed"),
+        }
+    }
+
+    progress!();
+    progress!("📜 Status history:");
+    let history = store.status_history(puzzle.id)?;
+    for change in &history {
+        progress!(
+            "   {}  {:<10} {}",
+            change.recorded_at,
+            change.status,
+            change.note.as_deref().unwrap_or_default()
+        );
+    }
+
+    Ok(serde_json::json!({
+        "puzzle": puzzle,
+        "funding_outpoints": outpoints,
+        "solve": solve,
+        "history": history,
+    }))
+}
@@ -0,0 +1,86 @@
+/*
+ * EXPORT - Write a puzzle's compiled program bundle
+ *
+ * Usage:
+ *   puzzle-hunt export --puzzle <puzzle_file.json> [--out <bundle.json>]
+ *
+ * Example:
+ *   puzzle-hunt export --puzzle puzzle_2cf24dba.json --out puzzle_2cf24dba_program.json
+ *
+ * The bundle has everything needed to spend the puzzle with other
+ * tools, except the secret:
+ *   - the contract source and its TARGET_HASH argument
+ *   - the compiled program (base64, without witness) and its CMR
+ *   - the Taproot leaf script, leaf version, internal key and control block
+ *   - the address and outpoint
+ *
+ * Without --out the bundle is printed.
+ */
+
+use crate::chain;
+use crate::contract::{self, PuzzleContract};
+use crate::progress;
+use anyhow::{Context, Result};
+use base64::Engine;
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Public puzzle file (puzzle_XXXX.json)
+    #[arg(long)]
+    pub puzzle: String,
+    /// Write the bundle to this file instead of printing it
+    #[arg(long, value_name = "FILE")]
+    pub out: Option<String>,
+}
+
+/// The program bundle of a puzzle file.
+pub fn bundle(puzzle: &serde_json::Value) -> Result<serde_json::Value> {
+    let target_hash = puzzle["target_hash"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing target_hash in puzzle file"))?;
+    let network = puzzle["network"].as_str().unwrap_or("liquidtestnet");
+    let contract = PuzzleContract::compile(contract::parse_target_hash(target_hash)?)?;
+    let program = contract.compiled.commit().to_vec_without_witness();
+    let leaf_version: u8 = simplicity::leaf_version().into();
+
+    Ok(serde_json::json!({
+        "contract": "puzzle_jackpot",
+        "source": contract::PUZZLE_CONTRACT,
+        "arguments": { "TARGET_HASH": target_hash },
+        "program": base64::engine::general_purpose::STANDARD.encode(program),
+        "cmr": hex::encode(contract.cmr().as_ref()),
+        "leaf_script": hex::encode(contract.script.as_bytes()),
+        "leaf_version": format!("0x{:02x}", leaf_version),
+        "internal_key": contract::INTERNAL_KEY,
+        "control_block": hex::encode(contract.control_block().serialize()),
+        "address": contract.address(None, chain::address_params_for(network)).to_string(),
+        "network": network,
+        "txid": puzzle["txid"],
+        "vout": puzzle["vout"],
+        "witness": { "SECRET": "u256, SHA256(SECRET) == TARGET_HASH" },
+    }))
+}
+
+pub fn run(args: ExportArgs) -> Result<serde_json::Value> {
+    let puzzle: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&args.puzzle).context("Failed to read puzzle file")?,
+    )
+    .context("Failed to parse puzzle JSON")?;
+    let bundle = bundle(&puzzle)?;
+
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, serde_json::to_string_pretty(&bundle)?)
+                .with_context(|| format!("Failed to write {}", path))?;
+            progress!("📦 Program bundle written to {}", path);
+            progress!("   CMR: 0x{}", bundle["cmr"].as_str().unwrap_or_default());
+            Ok(serde_json::json!({ "file": path, "cmr": bundle["cmr"] }))
+        }
+        None => {
+            if !crate::output::is_json() {
+                println!("{}", serde_json::to_string_pretty(&bundle)?);
+            }
+            Ok(bundle)
+        }
+    }
+}
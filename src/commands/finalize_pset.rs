@@ -0,0 +1,167 @@
+/*
+ * FINALIZE PSET - Broadcast a puzzle spend combined with other wallets
+ *
+ * Usage:
+ *   puzzle-hunt finalize-pset --pset <pset_file | base64> [--dry-run]
+ *
+ * Examples:
+ *   puzzle-hunt solve --pset-out solve.pset --puzzle puzzle_2cf24dba.json --secret "satoshi" --to tex1q...
+ *   (add and sign fee inputs/outputs with another wallet)
+ *   puzzle-hunt finalize-pset --pset solve.pset
+ *
+ * Accepts the PSET written by solve --pset-out after other wallets have
+ * added their own inputs and outputs and signed them. Every input must
+ * be finalized. The Simplicity inputs are run locally against the
+ * combined transaction before it is broadcast, and the spend is saved
+ * in the puzzle database like solve's.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use crate::execution::{self, SpendContext};
+use crate::fees;
+use crate::output::{self, ErrorCode};
+use crate::progress;
+use crate::pset;
+use crate::store::{self, PuzzleStore, SpendRecord};
+use crate::wallet;
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::Path;
+
+#[derive(Debug, Args)]
+pub struct FinalizePsetArgs {
+    /// PSET file, or the PSET itself in base64
+    #[arg(long, value_name = "FILE|BASE64")]
+    pub pset: String,
+    /// Check the transaction with testmempoolaccept without broadcasting it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+pub fn run(args: FinalizePsetArgs) -> Result<serde_json::Value> {
+    let encoded = if Path::new(&args.pset).is_file() {
+        std::fs::read_to_string(&args.pset).with_context(|| format!("Failed to read {}", args.pset))?
+    } else {
+        args.pset.clone()
+    };
+    let pset = wallet::decode_pset(&encoded)?;
+
+    progress!("📦 PSET with {} inputs and {} outputs", pset.inputs().len(), pset.outputs().len());
+    let simplicity_inputs = pset::simplicity_inputs(&pset)?;
+    if simplicity_inputs.is_empty() {
+        return Err(anyhow::anyhow!("❌ PSET has no Simplicity input; was it written by solve --pset-out?"));
+    }
+
+    let (tx, spent_utxos) = pset::finalize(&pset)?;
+    progress!("✅ All inputs finalized");
+    progress!();
+
+    let cli = ElementsCli::from_config()?;
+
+    // The other wallets changed the transaction: run the programs again
+    progress!("🧪 Running Simplicity inputs locally");
+    let genesis_hash = execution::genesis_hash(&cli)?;
+    for input in &simplicity_inputs {
+        let context = SpendContext {
+            tx: &tx,
+            spent_utxos: &spent_utxos,
+            input_index: input.index,
+            script_cmr: input.cmr,
+            control_block: &input.control_block,
+            annex: input.annex.clone(),
+            genesis_hash,
+        };
+        match execution::run(&input.program, &context) {
+            Ok(()) => progress!("   ✅ Input {} executed successfully", input.index),
+            Err(failure) => {
+                return Err(output::error(
+                    ErrorCode::ExecutionFailed,
+                    format!("❌ Simplicity input {} would be rejected: {}", input.index, failure),
+                ));
+            }
+        }
+    }
+    progress!();
+
+    let fee = tx
+        .output
+        .iter()
+        .filter(|o| o.is_fee())
+        .filter_map(|o| o.value.explicit())
+        .map(Amount::from_sat)
+        .sum::<Amount>();
+    let vsize = fees::discount_vsize(&tx);
+    let tx_hex = hex::encode(elements::encode::serialize(&tx));
+    progress!("⚖️  Fee: {} sats over {} vB ({:.2} sat/vB)", fee.to_sat(), vsize, fees::effective_rate(fee, vsize));
+    progress!("   🆔 TXID: {}", tx.txid());
+    progress!();
+
+    let mut result = serde_json::json!({
+        "txid": tx.txid().to_string(),
+        "fee_sats": fee,
+        "fee_rate": fees::effective_rate(fee, vsize),
+        "vsize": vsize,
+        "tx_hex": &tx_hex,
+        "broadcast": false,
+    });
+
+    if args.dry_run {
+        progress!("🧪 Dry run: the transaction will NOT be broadcast");
+        let accept = cli.call_json(&["testmempoolaccept", &serde_json::json!([tx_hex]).to_string()])?;
+        progress!("🔤 Transaction hex:");
+        progress!("{}", tx_hex);
+        progress!();
+        if accept[0]["allowed"].as_bool() != Some(true) {
+            let reason = accept[0]["reject-reason"].as_str().unwrap_or("unknown reason");
+            return Err(output::error(
+                ErrorCode::BroadcastRejected,
+                format!("❌ The node would reject this transaction: {}", reason),
+            ));
+        }
+        progress!("   ✅ The node would accept this transaction");
+        result["dry_run"] = true.into();
+        result["mempool_accept"] = accept[0].clone();
+        return Ok(result);
+    }
+
+    // Keep the spend, keyed by the first puzzle input, like solve does
+    let puzzle_outpoint = tx.input[simplicity_inputs[0].index].previous_output;
+    let spend = SpendRecord {
+        funding_txid: puzzle_outpoint.txid.to_string(),
+        funding_vout: puzzle_outpoint.vout,
+        txid: tx.txid().to_string(),
+        tx_hex: tx_hex.clone(),
+        fee,
+        vsize,
+        destination: None,
+        status: store::SPEND_BUILT.to_string(),
+        replaces: None,
+        created_at: String::new(),
+    };
+    let db = match PuzzleStore::open_default().and_then(|db| db.record_spend(&spend).map(|_| db)) {
+        Ok(db) => Some(db),
+        Err(e) => {
+            eprintln!("   ⚠️  Could not save the spend in {}: {}", PuzzleStore::default_path().display(), e);
+            None
+        }
+    };
+
+    progress!("📡 Broadcasting transaction...");
+    let broadcast = cli.call(&["sendrawtransaction", &tx_hex]);
+    if let Some(db) = &db {
+        let status = if broadcast.is_ok() { store::SPEND_BROADCAST } else { store::SPEND_FAILED };
+        if let Err(e) = db.set_spend_status(&spend.txid, status) {
+            eprintln!("   ⚠️  Could not update the saved spend: {}", e);
+        }
+    }
+    let txid = broadcast.map_err(|e| {
+        output::error(ErrorCode::BroadcastRejected, format!("Failed to broadcast transaction: {:#}", e))
+    })?;
+
+    progress!("✅ Transaction broadcast: {}", txid);
+    progress!("   ./elements-cli getrawtransaction {} true", txid);
+    result["txid"] = txid.into();
+    result["broadcast"] = true.into();
+    Ok(result)
+}
@@ -0,0 +1,113 @@
+/*
+ * LEADERBOARD - Rank participants by their on-chain solves
+ *
+ * Usage:
+ *   puzzle-hunt leaderboard [--scan] [--exclude <address> ...] [--out <file.json>]
+ *
+ * Examples:
+ *   puzzle-hunt leaderboard
+ *   puzzle-hunt leaderboard --scan --exclude tex1q...organizer
+ *   puzzle-hunt leaderboard --out leaderboard.json
+ *
+ * Winners are grouped by the address their prize went to, with their
+ * number of solves, total prize and time to solve each puzzle (see
+ * leaderboard.rs). --scan first looks on-chain for the solve of solved
+ * puzzles that have none recorded yet, as list does.
+ */
+
+use crate::chain::ElementsCli;
+use crate::commands::list;
+use crate::leaderboard::{self, Leaderboard};
+use crate::output;
+use crate::progress;
+use crate::store::{self, PuzzleStore};
+use anyhow::{Context, Result};
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct LeaderboardArgs {
+    /// Find unrecorded solves on-chain first (needs the node)
+    #[arg(long)]
+    pub scan: bool,
+    /// Leave out solves paid to this address, e.g. your reclaims (repeatable)
+    #[arg(long, value_name = "ADDRESS")]
+    pub exclude: Vec<String>,
+    /// Also write the leaderboard as JSON to this file
+    #[arg(long, value_name = "FILE")]
+    pub out: Option<String>,
+}
+
+pub fn run(args: LeaderboardArgs) -> Result<serde_json::Value> {
+    let db = PuzzleStore::open_default()?;
+
+    if args.scan {
+        scan(&db)?;
+    }
+
+    let board = leaderboard::build(&db, &args.exclude)?;
+    let report = serde_json::to_value(&board)?;
+
+    if let Some(out) = &args.out {
+        std::fs::write(out, serde_json::to_string_pretty(&report)?).with_context(|| format!("Failed to write {}", out))?;
+        progress!("💾 Leaderboard written to {}", out);
+    }
+    if !output::is_json() {
+        print(&board);
+    }
+
+    Ok(report)
+}
+
+/// Record the solves of spent puzzles that have none yet.
+fn scan(db: &PuzzleStore) -> Result<()> {
+    let cli = ElementsCli::from_config()?;
+    if !cli.is_available() {
+        progress!("⚠️  Elements node not available, using recorded solves only");
+        return Ok(());
+    }
+    progress!("🔍 Looking for unrecorded solves...");
+    for puzzle in db.list_puzzles()? {
+        let spent = puzzle.status == store::STATUS_SOLVED || puzzle.status == store::STATUS_ARCHIVED;
+        if spent && db.solve_for(puzzle.id)?.is_none() {
+            if let Some(solve) = list::detect_solve(&cli, db, &puzzle)? {
+                progress!("   Found solve of {}: {}", puzzle.target_hash, solve.txid);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print(board: &Leaderboard) {
+    println!();
+    println!("🏆 LEADERBOARD");
+    println!("==============");
+    if board.participants.is_empty() {
+        println!("No solves recorded yet.");
+    }
+    for participant in &board.participants {
+        println!();
+        let name = match &participant.nickname {
+            Some(nickname) => format!("{} ({})", nickname, participant.address),
+            None => participant.address.clone(),
+        };
+        println!("#{} {}  {} solve(s), {} L-BTC", participant.rank, name, participant.solves, participant.total_prize);
+        for puzzle in &participant.puzzles {
+            let time = puzzle
+                .time_to_solve_secs
+                .map(leaderboard::format_duration)
+                .unwrap_or_else(|| "unconfirmed".to_string());
+            println!(
+                "   {}...  {} L-BTC  in {}  ({})",
+                &puzzle.target_hash.chars().take(18).collect::<String>(),
+                puzzle.prize,
+                time,
+                puzzle.txid
+            );
+        }
+    }
+    println!();
+    println!("📊 {} puzzle(s) solved, {} L-BTC won", board.solved_puzzles, board.total_prize);
+    if board.skipped > 0 {
+        println!("⚠️  {} solve(s) left out: secret not verified or winner unknown", board.skipped);
+    }
+}
@@ -0,0 +1,441 @@
+/*
+ * LIST - List, verify and manage puzzle status
+ *
+ * Usage:
+ *   puzzle-hunt list [--auto] [--network <chain>] [--type <contract>] [--status <status>]
+ *
+ * Examples:
+ *   puzzle-hunt list                              # Interactive mode
+ *   puzzle-hunt list --auto                       # Auto-archive mode (for cron)
+ *   puzzle-hunt --output json list --status active
+ *
+ * This will:
+ * 1. Import any new puzzle_*.json files from the current directory
+ * 2. Check every funding outpoint with gettxout
+ * 3. Classify each puzzle as active, solved or unknown (node unavailable)
+ * 4. Find the solving transaction of solved puzzles: winner address,
+ *    revealed secret, block height and time
+ * 5. Record status changes and solves in the puzzle database
+ * 6. Offer to archive solved puzzles into archived_puzzles/
+ *
+ * --json is kept as a shorthand for --output json.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use crate::output;
+use crate::secret_file;
+use crate::solves::{self, SolveInfo};
+use crate::store::{self, ImportOutcome, PuzzleRecord, PuzzleStore};
+use anyhow::Result;
+use clap::Args;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const ARCHIVE_DIR: &str = "archived_puzzles";
+
+#[derive(Debug, Default, Args)]
+pub struct ListArgs {
+    /// Archive solved puzzles without prompting
+    #[arg(long, short)]
+    pub auto: bool,
+    /// Same as --output json
+    #[arg(long)]
+    pub json: bool,
+    /// Only show puzzles on this chain (e.g. liquidtestnet)
+    #[arg(long, value_name = "CHAIN")]
+    pub network: Option<String>,
+    /// Only show puzzles using this contract (e.g. puzzle_jackpot)
+    #[arg(long = "type", value_name = "CONTRACT")]
+    pub contract: Option<String>,
+    /// Only show puzzles with this status
+    #[arg(long, value_parser = store::STATUSES)]
+    pub status: Option<String>,
+}
+
+pub fn run(options: ListArgs) -> Result<serde_json::Value> {
+    let options = &options;
+    let json = output::is_json();
+    let db = PuzzleStore::open_default()?;
+    let cli = ElementsCli::from_config()?;
+
+    if !json {
+        if options.auto {
+            println!();
+            println!("🤖 AUTO-ARCHIVE MODE");
+            println!("========================");
+            println!("{}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+            println!();
+        } else {
+            println!();
+            println!("╔══════════════════════════════════════╗");
+            println!("║       🎯 PUZZLE HUNT STATUS 🎯       ║");
+            println!("╚══════════════════════════════════════╝");
+            println!();
+            println!("💡 Tip: Use 'list-puzzles --auto' for automatic archiving");
+            println!();
+        }
+    }
+
+    // 1. Pick up puzzle files created outside the database
+    let imported = import_new_files(&db)?;
+    if imported > 0 && !json {
+        println!("📥 Imported {} new puzzle file(s) into the database", imported);
+        println!();
+    }
+
+    // 2. Check each puzzle on-chain
+    let node_available = cli.is_available();
+    if !json {
+        println!("🔍 Scanning puzzles...");
+        if !node_available {
+            println!("⚠️  Elements node not available, status cannot be verified");
+        }
+        println!();
+    }
+
+    let mut entries = vec![];
+    for puzzle in db.list_puzzles()? {
+        if !matches_filter(&puzzle, options, &["network", "type"]) {
+            continue;
+        }
+
+        let status = if puzzle.status == store::STATUS_ARCHIVED {
+            store::STATUS_ARCHIVED.to_string()
+        } else {
+            let status = check_status(&cli, &db, &puzzle, node_available)?;
+            if status != store::STATUS_UNKNOWN {
+                db.set_status(puzzle.id, &status, Some("list-puzzles"))?;
+            }
+            status
+        };
+
+        let mut puzzle = puzzle;
+        puzzle.status = status;
+        if matches_filter(&puzzle, options, &["status"]) {
+            entries.push(puzzle);
+        }
+    }
+
+    // Archived puzzles are only listed when asked for
+    if options.status.as_deref() != Some(store::STATUS_ARCHIVED) {
+        entries.retain(|p| p.status != store::STATUS_ARCHIVED);
+    }
+
+    // 3. Identify the winners of solved puzzles
+    let mut solves = HashMap::new();
+    for puzzle in &entries {
+        if puzzle.status == store::STATUS_SOLVED || puzzle.status == store::STATUS_ARCHIVED {
+            let solve = match db.solve_for(puzzle.id)? {
+                Some(solve) if solve.block_height.is_some() || !node_available => Some(solve),
+                recorded => detect_solve(&cli, &db, puzzle)?.or(recorded),
+            };
+            if let Some(solve) = solve {
+                solves.insert(puzzle.id, solve);
+            }
+        }
+    }
+
+    // 4. Report and archive
+    let total_active: Amount = entries
+        .iter()
+        .filter(|p| p.status == store::STATUS_ACTIVE)
+        .filter_map(|p| p.amount_sats)
+        .sum();
+
+    let mut archived = vec![];
+    for puzzle in &entries {
+        if !json {
+            print_puzzle(puzzle, solves.get(&puzzle.id));
+        }
+
+        if puzzle.status == store::STATUS_SOLVED {
+            let archive = options.auto || (!json && ask("   Archive this solved puzzle? (y/n) ")?);
+            if archive {
+                archive_puzzle(&db, puzzle, json)?;
+                archived.push(puzzle.target_hash.clone());
+            }
+        }
+
+        if !json {
+            println!();
+        }
+    }
+
+    let count = |status: &str| entries.iter().filter(|p| p.status == status).count();
+
+    if json {
+        let report = serde_json::json!({
+            "node_available": node_available,
+            "total": entries.len(),
+            "active": count(store::STATUS_ACTIVE),
+            "solved": count(store::STATUS_SOLVED),
+            "unknown": count(store::STATUS_UNKNOWN),
+            "total_active_sats": total_active,
+            "archived_now": archived,
+            "puzzles": entries
+                .iter()
+                .map(|p| {
+                    let mut entry = serde_json::to_value(p)?;
+                    entry["solve"] = serde_json::to_value(solves.get(&p.id))?;
+                    Ok(entry)
+                })
+                .collect::<Result<Vec<_>>>()?,
+        });
+        return Ok(report);
+    }
+
+    println!();
+    println!("╔══════════════════════════════════════╗");
+    println!("║           📊 SUMMARY                 ║");
+    println!("╚══════════════════════════════════════╝");
+    println!();
+    println!("📈 Statistics:");
+    println!("   Total puzzles found: {}", entries.len());
+    println!("   Active puzzles: {}", count(store::STATUS_ACTIVE));
+    println!("   Solved puzzles: {}", count(store::STATUS_SOLVED));
+    if count(store::STATUS_UNKNOWN) > 0 {
+        println!("   Unknown status: {}", count(store::STATUS_UNKNOWN));
+    }
+    println!();
+
+    if count(store::STATUS_ACTIVE) > 0 {
+        println!(
+            "💰 Total prize pool active: {} sats ({} L-BTC)",
+            total_active.to_sat(),
+            total_active
+        );
+        println!();
+        println!("💡 To solve a puzzle:");
+        println!("   cargo run --bin solve-puzzle -- <puzzle_file> <secret> <your_address>");
+    }
+
+    if entries.is_empty() {
+        println!("❌ No puzzles found.");
+        println!();
+        println!("💡 To create a puzzle, use:");
+        println!("   cargo run --bin create-puzzle -- \"secret\" 0.1 \"Your hint here\"");
+    }
+
+    if options.auto {
+        println!();
+        println!(
+            "[{}] Processed: {} puzzles, Archived: {}, Active: {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            entries.len(),
+            archived.len(),
+            count(store::STATUS_ACTIVE)
+        );
+    } else {
+        println!();
+        println!("═══════════════════════════════════════");
+        println!("        Happy Puzzle Hunting! 🎯");
+        println!("═══════════════════════════════════════");
+        println!();
+    }
+
+    Ok(serde_json::Value::Null)
+}
+
+/// Import puzzle_*.json files from the working directory that the
+/// database doesn't know yet.
+fn import_new_files(db: &PuzzleStore) -> Result<usize> {
+    let mut imported = 0;
+    for entry in std::fs::read_dir(".")? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !name.starts_with("puzzle_") || !name.ends_with(".json") || name.ends_with("_SECRET.json") {
+            continue;
+        }
+        match db.import_json_file(&path) {
+            Ok(ImportOutcome::Inserted) => imported += 1,
+            Ok(ImportOutcome::AlreadyPresent) => {}
+            Err(e) => eprintln!("⚠️  Invalid puzzle file {}: {}", path.display(), e),
+        }
+    }
+    Ok(imported)
+}
+
+fn matches_filter(puzzle: &PuzzleRecord, options: &ListArgs, fields: &[&str]) -> bool {
+    fields.iter().all(|field| match *field {
+        "network" => options.network.as_ref().is_none_or(|n| &puzzle.network == n),
+        "type" => options.contract.as_ref().is_none_or(|c| &puzzle.contract == c),
+        "status" => options.status.as_ref().is_none_or(|s| &puzzle.status == s),
+        _ => true,
+    })
+}
+
+/// Active while any funding outpoint is unspent, solved once all are spent.
+fn check_status(cli: &ElementsCli, db: &PuzzleStore, puzzle: &PuzzleRecord, node_available: bool) -> Result<String> {
+    let outpoints = db.funding_outpoints(puzzle.id)?;
+    if !node_available || outpoints.is_empty() {
+        return Ok(store::STATUS_UNKNOWN.to_string());
+    }
+
+    for outpoint in &outpoints {
+        match cli.get_tx_out(&outpoint.txid, outpoint.vout) {
+            Ok(Some(_)) => return Ok(store::STATUS_ACTIVE.to_string()),
+            Ok(None) => {}
+            Err(_) => return Ok(store::STATUS_UNKNOWN.to_string()),
+        }
+    }
+
+    Ok(store::STATUS_SOLVED.to_string())
+}
+
+/// Look for the spend of each funding outpoint, record every one found
+/// and return the first.
+pub fn detect_solve(cli: &ElementsCli, db: &PuzzleStore, puzzle: &PuzzleRecord) -> Result<Option<SolveInfo>> {
+    let mut first = None;
+    for outpoint in db.funding_outpoints(puzzle.id)? {
+        if let Some(mut solve) = solves::find_solve(cli, db, &outpoint.txid, outpoint.vout, &puzzle.target_hash)? {
+            db.record_solve(puzzle.id, &outpoint.txid, outpoint.vout, &solve)?;
+            db.name_winner(&mut solve)?;
+            first.get_or_insert(solve);
+        }
+    }
+    Ok(first)
+}
+
+fn print_solve(solve: &SolveInfo) {
+    let destination = solve.destination.as_deref().unwrap_or("unknown");
+    match &solve.winner {
+        Some(winner) => println!("   🏅 Winner: {} ({})", winner, destination),
+        None => println!("   🏅 Winner: {}", destination),
+    }
+    println!("   📦 Solved by: {}", solve.txid);
+    match (&solve.secret_text, &solve.secret_hex) {
+        (Some(text), _) => println!("   🔓 Secret: \"{}\"", text),
+        (None, Some(hex)) => println!("   🔓 Secret: 0x{}", hex),
+        _ => {}
+    }
+    if solve.secret_hex.is_some() {
+        println!("   {} Secret matches target hash", if solve.verified { "✓" } else { "⚠️" });
+    }
+    match (solve.block_height, solve.block_time) {
+        (Some(height), Some(time)) => {
+            let time = chrono::DateTime::from_timestamp(time, 0)
+                .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            println!("   ⛓️  Block {} at {}", height, time);
+        }
+        (Some(height), None) => println!("   ⛓️  Block {}", height),
+        _ => println!("   ⏳ Unconfirmed (in mempool)"),
+    }
+}
+
+fn print_puzzle(puzzle: &PuzzleRecord, solve: Option<&SolveInfo>) {
+    let short = |s: &str| s.chars().take(20).collect::<String>();
+    let amount = puzzle.amount_sats.map_or("?".to_string(), |a| a.to_string());
+    let hint = puzzle.hint.as_deref().unwrap_or("No hint provided");
+
+    match puzzle.status.as_str() {
+        store::STATUS_ACTIVE => {
+            println!("✅ ACTIVE PUZZLE");
+            println!("   📄 File: {}", puzzle.public_file.as_deref().unwrap_or("-"));
+            println!("   📍 Address: {}...", short(&puzzle.address));
+            println!("   💰 Prize: {} L-BTC", amount);
+            println!("   💡 Hint: \"{}\"", hint);
+            println!("   🔐 Hash: {}...", short(&puzzle.target_hash));
+            println!("   📅 Created: {}", puzzle.created_at.as_deref().unwrap_or("Unknown"));
+        }
+        store::STATUS_SOLVED => {
+            println!("🏆 SOLVED PUZZLE");
+            println!("   📄 File: {}", puzzle.public_file.as_deref().unwrap_or("-"));
+            println!("   📍 Address: {}...", short(&puzzle.address));
+            println!("   💰 Prize was: {} L-BTC", amount);
+            println!("   💡 Hint was: \"{}\"", hint);
+            if let Some(solve) = solve {
+                print_solve(solve);
+            }
+        }
+        store::STATUS_ARCHIVED => {
+            println!("🗄️  ARCHIVED PUZZLE");
+            println!("   📄 File: {}", puzzle.public_file.as_deref().unwrap_or("-"));
+            println!("   💰 Prize was: {} L-BTC", amount);
+            if let Some(solve) = solve {
+                print_solve(solve);
+            }
+        }
+        _ => {
+            println!("❓ UNKNOWN STATUS");
+            println!("   📄 File: {}", puzzle.public_file.as_deref().unwrap_or("-"));
+            println!("   📍 Address: {}...", short(&puzzle.address));
+            println!("   💰 Prize: {} L-BTC", amount);
+            println!("   💡 Hint: \"{}\"", hint);
+            println!("   ⚠️  Cannot verify UTXO status");
+        }
+    }
+}
+
+fn ask(question: &str) -> Result<bool> {
+    print!("{}", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y"))
+}
+
+/// Move the public and SECRET files into archived_puzzles/ with a
+/// timestamp prefix and mark the puzzle archived.
+fn archive_puzzle(db: &PuzzleStore, puzzle: &PuzzleRecord, quiet: bool) -> Result<()> {
+    std::fs::create_dir_all(ARCHIVE_DIR)?;
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+
+    let move_file = |file: &Path| -> Result<String> {
+        let name = file.file_name().and_then(|n| n.to_str()).unwrap_or("puzzle.json");
+        let target = Path::new(ARCHIVE_DIR).join(format!("{}_{}", timestamp, name));
+        std::fs::rename(file, &target)?;
+        Ok(target.display().to_string())
+    };
+
+    let public_file = match puzzle.public_file.as_deref().map(Path::new) {
+        Some(file) if file.exists() => Some(move_file(file)?),
+        other => other.map(|p| p.display().to_string()),
+    };
+    if let Some(file) = &public_file {
+        if !quiet {
+            println!("   ✓ Archived to: {}", file);
+        }
+    }
+
+    let secret_file = match find_secret_file(puzzle) {
+        Some(file) => {
+            let archived = move_file(&file)?;
+            if !quiet {
+                println!("   ✓ Secret archived to: {}", archived);
+            }
+            Some(archived)
+        }
+        None => puzzle.secret_file.clone(),
+    };
+
+    db.set_files(puzzle.id, public_file.as_deref(), secret_file.as_deref())?;
+    db.set_status(puzzle.id, store::STATUS_ARCHIVED, Some("archived by list-puzzles"))?;
+    Ok(())
+}
+
+/// The SECRET file recorded in the database, or one in the working
+/// directory whose target hash matches (read without decrypting).
+fn find_secret_file(puzzle: &PuzzleRecord) -> Option<PathBuf> {
+    if let Some(file) = puzzle.secret_file.as_deref().map(PathBuf::from) {
+        if file.exists() {
+            return Some(file);
+        }
+    }
+
+    std::fs::read_dir(".").ok()?.filter_map(|e| e.ok()).map(|e| e.path()).find(|path| {
+        let is_secret = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("puzzle_") && n.ends_with("_SECRET.json"));
+        is_secret
+            && std::fs::read_to_string(path)
+                .ok()
+                .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+                .is_some_and(|file| secret_file::target_hash(&file) == Some(puzzle.target_hash.as_str()))
+    })
+}
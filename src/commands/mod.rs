@@ -0,0 +1,217 @@
+/*
+ * COMMANDS - The puzzle-hunt command line
+ *
+ * One binary with a subcommand per task:
+ *   create      create and fund a puzzle
+ *   solve       solve a puzzle and claim its prize
+ *   list        list puzzles and check their status on-chain
+ *   verify      check a puzzle file against its contract and the chain
+ *   add-to-pot  send more funds to a puzzle
+ *   reclaim     take back an unsolved prize with the SECRET file
+ *   export      write a puzzle's compiled program bundle
+ *   release-hints publish scheduled hints that are due
+ *   unlock-hints  decrypt locked hints whose key is public
+ *   render-site write a static web page of the puzzle board
+ *   leaderboard rank winners by their on-chain solves
+ *   sign-nickname sign a nickname registration (participants)
+ *   register    record a participant's signed nickname (organizer)
+ *   db          manage the local puzzle database
+ *   watch       follow puzzles on-chain and report their events
+ *   bump-fee    replace a pending puzzle spend with a higher fee
+ *   finalize-pset broadcast a puzzle spend combined with other wallets
+ *   reveal-secret decrypt a SECRET file
+ *   completions print shell completions
+ *
+ * --output json works with every subcommand (see output.rs).
+ *
+ * create-puzzle, solve-puzzle, list-puzzles, puzzle-db, puzzle-watcher,
+ * bump-fee, finalize-pset and reveal-secret are kept as aliases: their
+ * positional arguments are turned into the named flags here.
+ */
+
+pub mod add_to_pot;
+pub mod bump_fee;
+pub mod create;
+pub mod db;
+pub mod export;
+pub mod finalize_pset;
+pub mod leaderboard;
+pub mod list;
+pub mod reclaim;
+pub mod register;
+pub mod release_hints;
+pub mod render_site;
+pub mod reveal_secret;
+pub mod sign_nickname;
+pub mod solve;
+pub mod unlock_hints;
+pub mod verify;
+pub mod watch;
+
+use crate::output::{self, ErrorCode, OutputFormat};
+use anyhow::Result;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::ffi::OsString;
+
+#[derive(Debug, Parser)]
+#[command(name = "puzzle-hunt", version, about = "Simplicity puzzle hunts on Liquid")]
+pub struct Cli {
+    /// Human-readable text, or one JSON object on stdout with progress on stderr
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Create and fund a new puzzle
+    Create(create::CreateArgs),
+    /// Solve a puzzle and claim its prize
+    Solve(solve::SolveArgs),
+    /// List puzzles and check their status on-chain
+    List(list::ListArgs),
+    /// Check a puzzle file against its contract and the chain
+    Verify(verify::VerifyArgs),
+    /// Send more funds to an existing puzzle
+    AddToPot(add_to_pot::AddToPotArgs),
+    /// Take back an unsolved puzzle's prize with its SECRET file
+    Reclaim(reclaim::ReclaimArgs),
+    /// Write a puzzle's compiled program bundle
+    Export(export::ExportArgs),
+    /// Publish scheduled hints whose release height or time has passed
+    ReleaseHints(release_hints::ReleaseHintsArgs),
+    /// Decrypt locked hints whose key is public
+    UnlockHints(unlock_hints::UnlockHintsArgs),
+    /// Write a static web site of active and solved puzzles
+    RenderSite(render_site::RenderSiteArgs),
+    /// Rank winning addresses by solves, prize won and time to solve
+    Leaderboard(leaderboard::LeaderboardArgs),
+    /// Sign a nickname with the key of the address you solve to
+    SignNickname(sign_nickname::SignNicknameArgs),
+    /// Verify and record a participant's signed nickname
+    Register(register::RegisterArgs),
+    /// Manage the local puzzle database
+    Db(db::DbArgs),
+    /// Follow puzzles on-chain, reporting their events as JSON lines
+    Watch(watch::WatchArgs),
+    /// Replace a pending puzzle spend with a higher-fee version
+    BumpFee(bump_fee::BumpFeeArgs),
+    /// Broadcast a puzzle spend PSET completed by other wallets
+    FinalizePset(finalize_pset::FinalizePsetArgs),
+    /// Decrypt a SECRET file, or encrypt a plaintext one
+    RevealSecret(reveal_secret::RevealSecretArgs),
+    /// Print shell completions, e.g. puzzle-hunt completions bash > /etc/bash_completion.d/puzzle-hunt
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+/// Run a parsed command line.
+pub fn run(cli: Cli) -> Result<()> {
+    let format = match &cli.command {
+        Command::List(args) if args.json => OutputFormat::Json,
+        _ => cli.output,
+    };
+    output::init(format);
+
+    let result = match cli.command {
+        Command::Create(args) => create::run(args),
+        Command::Solve(args) => solve::run(args),
+        Command::List(args) => list::run(args),
+        Command::Verify(args) => verify::run(args),
+        Command::AddToPot(args) => add_to_pot::run(args),
+        Command::Reclaim(args) => reclaim::run(args),
+        Command::Export(args) => export::run(args),
+        Command::ReleaseHints(args) => release_hints::run(args),
+        Command::UnlockHints(args) => unlock_hints::run(args),
+        Command::RenderSite(args) => render_site::run(args),
+        Command::Leaderboard(args) => leaderboard::run(args),
+        Command::SignNickname(args) => sign_nickname::run(args),
+        Command::Register(args) => register::run(args),
+        Command::Db(args) => db::run(args),
+        Command::Watch(args) => watch::run(args),
+        Command::BumpFee(args) => bump_fee::run(args),
+        Command::FinalizePset(args) => finalize_pset::run(args),
+        Command::RevealSecret(args) => reveal_secret::run(args),
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "puzzle-hunt", &mut std::io::stdout());
+            return Ok(());
+        }
+    };
+    output::finish(result)
+}
+
+/// Parse `argv` and run it. With --output json, a bad command line is
+/// reported as an invalid_arguments error object instead of clap's text.
+pub fn run_from<I, T>(argv: I) -> Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let argv: Vec<OsString> = argv.into_iter().map(Into::into).collect();
+    let err = match Cli::try_parse_from(&argv) {
+        Ok(cli) => return run(cli),
+        Err(err) => err,
+    };
+    let informational = matches!(
+        err.kind(),
+        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+    );
+    if informational || !wants_json(&argv) {
+        err.exit();
+    }
+
+    output::init(OutputFormat::Json);
+    let rendered = err.render().to_string();
+    // The error itself, without the usage and --help lines after it
+    let message: Vec<&str> = rendered.split("\n\n").next().unwrap_or_default().lines().map(str::trim).collect();
+    let message = message.join(" ");
+    output::finish(Err(output::error(ErrorCode::InvalidArguments, message.trim_start_matches("error: "))))
+}
+
+/// Whether `argv` asks for JSON output, as far as can be told when it
+/// does not parse.
+fn wants_json(argv: &[OsString]) -> bool {
+    argv.iter().enumerate().any(|(i, arg)| match arg.to_str() {
+        Some("--output=json") | Some("--json") => true,
+        Some("--output") => argv.get(i + 1).and_then(|v| v.to_str()) == Some("json"),
+        _ => false,
+    })
+}
+
+/// Run `puzzle-hunt <subcommand>` with this process's arguments, for the
+/// old binaries. Leading positional arguments become the named flags in
+/// `positional`, in order; flags are passed through unchanged.
+pub fn alias(subcommand: &str, positional: &[&str]) -> Result<()> {
+    let command = Cli::command();
+    let sub = command.find_subcommand(subcommand).expect("alias of a known subcommand");
+    let takes_value = |flag: &str| {
+        let long = flag.trim_start_matches("--");
+        sub.get_arguments()
+            .chain(command.get_arguments())
+            .any(|arg| arg.get_long() == Some(long) && arg.get_action().takes_values())
+    };
+
+    let mut argv = vec!["puzzle-hunt".to_string(), subcommand.to_string()];
+    let mut names = positional.iter();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg.starts_with('-') && arg.len() > 1 {
+            let value = if !arg.contains('=') && takes_value(&arg) { args.next() } else { None };
+            argv.push(arg);
+            argv.extend(value);
+        } else if let Some(name) = names.next() {
+            argv.push(format!("--{}", name));
+            argv.push(arg);
+        } else {
+            // Let clap report the extra argument
+            argv.push(arg);
+        }
+    }
+
+    run_from(argv)
+}
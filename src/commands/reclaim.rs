@@ -0,0 +1,94 @@
+/*
+ * RECLAIM - Take back an unsolved puzzle's prize as its creator
+ *
+ * Usage:
+ *   puzzle-hunt reclaim --secret-file <puzzle_XXXX_SECRET.json> [--to <address>]
+ *                       [--puzzle <puzzle_file.json>] [--identity <file>] [solve options]
+ *
+ * Example:
+ *   puzzle-hunt reclaim --secret-file puzzle_2cf24dba_SECRET.json
+ *
+ * The contract has no creator key: the only way back is to solve the
+ * puzzle with the secret, which the creator keeps in the encrypted
+ * SECRET file. This decrypts it and runs solve. The prize goes to
+ * --to, or to a new address of the node wallet.
+ *
+ * The public file defaults to the SECRET file name without _SECRET.
+ */
+
+use crate::chain::ElementsCli;
+use crate::commands::solve::{self, SolveArgs};
+use crate::progress;
+use crate::secret_file;
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::Path;
+
+#[derive(Debug, Args)]
+pub struct ReclaimArgs {
+    /// Encrypted SECRET file written by create
+    #[arg(long, value_name = "FILE")]
+    pub secret_file: String,
+    /// Public puzzle file [default: the SECRET file name without _SECRET]
+    #[arg(long)]
+    pub puzzle: Option<String>,
+    /// Address receiving the prize [default: a new node wallet address]
+    #[arg(long)]
+    pub to: Option<String>,
+    /// age identity file, for SECRET files encrypted to a recipient
+    #[arg(long, value_name = "FILE")]
+    pub identity: Option<String>,
+    /// Fee rate in sat/vB [default: estimatesmartfee]
+    #[arg(long, conflicts_with = "fee")]
+    pub fee_rate: Option<String>,
+    /// Absolute fee in sats
+    #[arg(long)]
+    pub fee: Option<String>,
+    /// Build and check the transaction without broadcasting it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+pub fn run(args: ReclaimArgs) -> Result<serde_json::Value> {
+    let private_info = secret_file::read_secret_file(
+        Path::new(&args.secret_file),
+        args.identity.as_deref().map(Path::new),
+    )?;
+    let secret = private_info["secret"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("SECRET file has no secret"))?
+        .to_string();
+
+    let puzzle = match args.puzzle {
+        Some(puzzle) => puzzle,
+        None => args
+            .secret_file
+            .strip_suffix("_SECRET.json")
+            .map(|stem| format!("{}.json", stem))
+            .context("Pass the public puzzle file with --puzzle")?,
+    };
+
+    let to = match args.to {
+        Some(to) => to,
+        None => ElementsCli::from_config()?
+            .call_wallet(&["getnewaddress"])
+            .context("Failed to get a wallet address; pass --to")?,
+    };
+
+    progress!("↩️  Reclaiming {} to {}", puzzle, to);
+    progress!();
+
+    solve::run(SolveArgs {
+        puzzle,
+        secret,
+        to,
+        blinding_key: None,
+        fee_rate: args.fee_rate,
+        fee: args.fee,
+        fee_from_wallet: false,
+        dry_run: args.dry_run,
+        pset_out: None,
+        outpoint: None,
+        funding_tx: None,
+        genesis_hash: None,
+    })
+}
@@ -0,0 +1,94 @@
+/*
+ * REGISTER - Record a participant's signed nickname
+ *
+ * Usage:
+ *   puzzle-hunt register --address <address> --nickname <nickname> --signed-at <unix> --signature <base64>
+ *   puzzle-hunt register --list
+ *
+ * Example:
+ *   puzzle-hunt register --address tex1q... --nickname alice --signed-at 1760000000 --signature H3k...
+ *
+ * Run by the organizer with what a participant sent: the signature must
+ * be made with the key of the address (see registration.rs, and
+ * sign-nickname for the participant's side). The nickname then names
+ * that address in solves, list, the leaderboard and the site.
+ * Registering again with a newer signature changes the nickname; one
+ * signed at or before the recorded registration is refused, so old
+ * signatures cannot be replayed.
+ */
+
+use crate::output::{self, ErrorCode};
+use crate::progress;
+use crate::registration;
+use crate::store::PuzzleStore;
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct RegisterArgs {
+    /// Address the participant solves to
+    #[arg(long, required_unless_present = "list", conflicts_with = "list")]
+    pub address: Option<String>,
+    /// Nickname to register
+    #[arg(long, required_unless_present = "list", conflicts_with = "list")]
+    pub nickname: Option<String>,
+    /// Time in the signed message, from sign-nickname (Unix time)
+    #[arg(long, value_name = "UNIX", required_unless_present = "list", conflicts_with = "list")]
+    pub signed_at: Option<u64>,
+    /// Signed message from sign-nickname, base64
+    #[arg(long, required_unless_present = "list", conflicts_with = "list")]
+    pub signature: Option<String>,
+    /// List registered participants instead
+    #[arg(long)]
+    pub list: bool,
+}
+
+pub fn run(args: RegisterArgs) -> Result<serde_json::Value> {
+    let db = PuzzleStore::open_default()?;
+
+    if args.list {
+        let participants = db.participants()?;
+        if participants.is_empty() {
+            progress!("No participants registered yet");
+        }
+        for participant in &participants {
+            progress!("👤 {}  {}  (since {})", participant.nickname, participant.address, participant.registered_at);
+        }
+        return Ok(serde_json::json!({ "participants": participants }));
+    }
+
+    let (Some(address), Some(nickname), Some(signed_at), Some(signature)) =
+        (args.address, args.nickname, args.signed_at, args.signature)
+    else {
+        return Err(output::error(
+            ErrorCode::InvalidArguments,
+            "--address, --nickname, --signed-at and --signature are required",
+        ));
+    };
+    register(&db, &address, &nickname, signed_at, &signature)
+}
+
+/// Verify a registration and store it. Shared with the HTTP API.
+pub fn register(
+    db: &PuzzleStore,
+    address: &str,
+    nickname: &str,
+    signed_at: u64,
+    signature: &str,
+) -> Result<serde_json::Value> {
+    let invalid = |e: anyhow::Error| output::error(ErrorCode::InvalidArguments, e.to_string());
+    registration::check_nickname(nickname).map_err(invalid)?;
+    let address = registration::unconfidential(address).map_err(invalid)?;
+    registration::verify(&address, nickname, signed_at, signature).map_err(invalid)?;
+    let last = db.participant(&address)?.map(|participant| participant.signed_at);
+    registration::check_signed_at(signed_at, last).map_err(invalid)?;
+    if let Some(owner) = db.participant_by_nickname(nickname)? {
+        if owner.address != address {
+            return Err(output::error(ErrorCode::InvalidArguments, format!("Nickname {} is already taken", owner.nickname)));
+        }
+    }
+    db.register_participant(&address, nickname, signature, signed_at).map_err(invalid)?;
+
+    progress!("✅ Registered {} as {}", address, nickname);
+    Ok(serde_json::json!({ "address": address, "nickname": nickname, "signed_at": signed_at }))
+}
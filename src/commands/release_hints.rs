@@ -0,0 +1,94 @@
+/*
+ * RELEASE HINTS - Publish scheduled hints that are due
+ *
+ * Usage:
+ *   puzzle-hunt release-hints [--puzzle <puzzle_file.json>] [--secret-file <file>]
+ *                             [--identity <file>]
+ *
+ * Examples:
+ *   puzzle-hunt release-hints
+ *   puzzle-hunt release-hints --puzzle puzzle_2cf24dba.json
+ *
+ * Without --puzzle, every active puzzle in the database with pending
+ * hints is checked. Hints whose block height or time has passed are
+ * copied from the encrypted SECRET file into the public file (see
+ * hints.rs). puzzle-watcher --release-hints does the same on each poll.
+ */
+
+use crate::chain::ElementsCli;
+use crate::hints::{self, HintReleaser};
+use crate::progress;
+use crate::store::PuzzleStore;
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Args)]
+pub struct ReleaseHintsArgs {
+    /// Only this public puzzle file [default: all active puzzles in the database]
+    #[arg(long)]
+    pub puzzle: Option<String>,
+    /// SECRET file of --puzzle [default: the puzzle file name with _SECRET]
+    #[arg(long, value_name = "FILE", requires = "puzzle")]
+    pub secret_file: Option<String>,
+    /// age identity file, for SECRET files encrypted to a recipient
+    #[arg(long, value_name = "FILE")]
+    pub identity: Option<PathBuf>,
+}
+
+pub fn run(args: ReleaseHintsArgs) -> Result<serde_json::Value> {
+    let cli = ElementsCli::from_config()?;
+
+    let released = match &args.puzzle {
+        Some(puzzle) => {
+            let secret_file = match &args.secret_file {
+                Some(secret_file) => secret_file.clone(),
+                None => puzzle
+                    .strip_suffix(".json")
+                    .map(|stem| format!("{}_SECRET.json", stem))
+                    .context("Pass the SECRET file with --secret-file")?,
+            };
+            let schedule = hints::read_schedule(Path::new(&secret_file), args.identity.as_deref())?;
+            let height: u64 = cli.call(&["getblockcount"])?.trim().parse().context("Invalid block count")?;
+            let released = hints::release_due(Path::new(puzzle), &schedule, height, chrono::Utc::now())?;
+
+            // Record the release like the watcher does, if the puzzle is known
+            let target_hash = puzzle_target_hash(puzzle)?;
+            if let Ok(db) = PuzzleStore::open_default() {
+                if let Ok(Some(record)) = db.find_by_hash(&target_hash) {
+                    for hint in &released {
+                        hints::released_event(&db, &record, hint, height)?;
+                    }
+                }
+            }
+            released
+                .into_iter()
+                .map(|hint| serde_json::json!({ "target_hash": target_hash, "index": hint.index, "hint": hint.hint }))
+                .collect::<Vec<_>>()
+        }
+        None => {
+            let db = PuzzleStore::open_default()?;
+            HintReleaser::new(args.identity.clone())
+                .release(&cli, &db)?
+                .into_iter()
+                .map(|event| serde_json::json!({ "target_hash": event.target_hash, "hint": event.detail }))
+                .collect()
+        }
+    };
+
+    if released.is_empty() {
+        progress!("💤 No hints are due");
+    }
+    for hint in &released {
+        progress!("💡 Released for {}: \"{}\"", hint["target_hash"].as_str().unwrap_or_default(), hint["hint"].as_str().unwrap_or_default());
+    }
+
+    Ok(serde_json::json!({ "released": released }))
+}
+
+fn puzzle_target_hash(puzzle: &str) -> Result<String> {
+    let public: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(puzzle)?)?;
+    public["target_hash"].as_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Missing target_hash in puzzle file"))
+}
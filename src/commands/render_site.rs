@@ -0,0 +1,323 @@
+/*
+ * RENDER SITE - Static web puzzle board from the puzzle database
+ *
+ * Usage:
+ *   puzzle-hunt render-site [--out <dir>] [--title <title>]
+ *
+ * Examples:
+ *   puzzle-hunt render-site
+ *   puzzle-hunt render-site --out /var/www/hunt --title "Liquid Summer Hunt"
+ *
+ * Writes a site that any static host can serve:
+ *   index.html                   board of active puzzles (prize, hints,
+ *                                target hash, address and its QR code) and
+ *                                the hall of fame of solved puzzles (winning
+ *                                secret, solve txid, winner address)
+ *   puzzles.json                 the same data, for scripts
+ *   puzzles/puzzle_<hash>.json   each public puzzle file, to download
+ *   qr/<hash>.svg                the address QR codes
+ *
+ * Files are named by the full target hash: two puzzles can share the
+ * 8-character prefix used elsewhere.
+ *
+ * Statuses and solves come from the database, so run list or keep the
+ * watcher running before rendering. Puzzle files are sanitized like the
+ * HTTP API's (server.rs): nothing from a SECRET file is published.
+ */
+
+use crate::amount::Amount;
+use crate::progress;
+use crate::server;
+use crate::solves::SolveInfo;
+use crate::store::{self, PuzzleRecord, PuzzleStore};
+use anyhow::{Context, Result};
+use clap::Args;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+#[derive(Debug, Args)]
+pub struct RenderSiteArgs {
+    /// Directory to write the site into
+    #[arg(long, value_name = "DIR", default_value = "site")]
+    pub out: String,
+    /// Page title
+    #[arg(long, default_value = "Simplicity Puzzle Hunt")]
+    pub title: String,
+}
+
+/// A puzzle as published on the site.
+#[derive(Debug, Serialize)]
+struct SitePuzzle {
+    target_hash: String,
+    network: String,
+    contract: String,
+    address: String,
+    status: String,
+    prize: Amount,
+    hints: Vec<String>,
+    created_at: Option<String>,
+    /// Path of the downloadable puzzle file, relative to the site
+    download: Option<String>,
+    /// Path of the address QR code, relative to the site
+    qr: Option<String>,
+    solve: Option<SolveInfo>,
+}
+
+pub fn run(args: RenderSiteArgs) -> Result<serde_json::Value> {
+    let db = PuzzleStore::open_default()?;
+    let out = Path::new(&args.out);
+    for dir in [out.to_path_buf(), out.join("puzzles"), out.join("qr")] {
+        std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    progress!("🌐 Rendering the puzzle board into {}/", args.out);
+
+    let mut active = vec![];
+    let mut solved = vec![];
+    for puzzle in db.list_puzzles()? {
+        let solve = db.solve_for(puzzle.id)?;
+        let board = match puzzle.status.as_str() {
+            store::STATUS_ACTIVE => true,
+            store::STATUS_SOLVED | store::STATUS_ARCHIVED if solve.is_some() => false,
+            _ => continue,
+        };
+        let site_puzzle = render_puzzle(&db, &puzzle, solve, out, board)?;
+        if board {
+            active.push(site_puzzle);
+        } else {
+            solved.push(site_puzzle);
+        }
+    }
+    // Newest puzzles and latest solves first
+    active.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    solved.sort_by_key(|p| std::cmp::Reverse(p.solve.as_ref().and_then(|s| s.block_height)));
+
+    let generated_at = chrono::Utc::now().to_rfc3339();
+    let data = serde_json::json!({
+        "title": args.title,
+        "generated_at": generated_at,
+        "active": active,
+        "solved": solved,
+    });
+    write(&out.join("puzzles.json"), &serde_json::to_string_pretty(&data)?)?;
+    write(&out.join("index.html"), &page(&args.title, &generated_at, &active, &solved))?;
+
+    progress!("✅ {} active and {} solved puzzle(s) written", active.len(), solved.len());
+    progress!("   Open {}", out.join("index.html").display());
+
+    Ok(serde_json::json!({
+        "out": args.out,
+        "active": active.len(),
+        "solved": solved.len(),
+    }))
+}
+
+fn render_puzzle(db: &PuzzleStore, puzzle: &PuzzleRecord, solve: Option<SolveInfo>, out: &Path, board: bool) -> Result<SitePuzzle> {
+    let hash = puzzle.target_hash.trim_start_matches("0x");
+
+    // An unreadable puzzle file only costs the download link
+    let public = match server::public_puzzle(puzzle) {
+        Ok(public) => Some(public),
+        Err(e) => {
+            progress!("⚠️  No download for {}: {}", puzzle.target_hash, e);
+            None
+        }
+    };
+    let download = match &public {
+        Some(public) => {
+            let file = format!("puzzles/puzzle_{}.json", hash);
+            write(&out.join(&file), &serde_json::to_string_pretty(public)?)?;
+            Some(file)
+        }
+        None => None,
+    };
+
+    // Solved puzzles no longer take funds, so they get no QR code
+    let qr = if board {
+        let file = format!("qr/{}.svg", hash);
+        let code = QrCode::new(puzzle.address.as_bytes()).context("Failed to encode address as QR code")?;
+        write(&out.join(&file), &code.render::<svg::Color>().min_dimensions(200, 200).build())?;
+        Some(file)
+    } else {
+        None
+    };
+
+    let outpoints = db.funding_outpoints(puzzle.id)?;
+    let prize = if outpoints.is_empty() {
+        puzzle.amount_sats.unwrap_or(Amount::ZERO)
+    } else {
+        outpoints.iter().filter_map(|o| o.amount_sats).sum()
+    };
+
+    Ok(SitePuzzle {
+        target_hash: puzzle.target_hash.clone(),
+        network: puzzle.network.clone(),
+        contract: puzzle.contract.clone(),
+        address: puzzle.address.clone(),
+        status: puzzle.status.clone(),
+        prize,
+        hints: hints(puzzle, public.as_ref()),
+        created_at: puzzle.created_at.clone(),
+        download,
+        qr,
+        solve,
+    })
+}
+
+/// The fixed hint, released scheduled hints and unlocked locked hints.
+fn hints(puzzle: &PuzzleRecord, public: Option<&serde_json::Value>) -> Vec<String> {
+    let mut hints: Vec<String> = puzzle.hint.iter().filter(|h| !h.is_empty()).cloned().collect();
+    if let Some(public) = public {
+        for field in ["hints", "locked_hints"] {
+            let released = public[field].as_array().into_iter().flatten();
+            hints.extend(released.filter_map(|h| h["hint"].as_str()).map(str::to_string));
+        }
+    }
+    hints
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; max-width: 960px; margin: 0 auto; padding: 1em; background: #fafafa; color: #222; }
+h1 { margin-bottom: 0; }
+.generated { color: #777; font-size: 0.9em; }
+.card { background: #fff; border: 1px solid #ddd; border-radius: 8px; padding: 1em; margin: 1em 0; display: flex; gap: 1em; }
+.card img { width: 160px; height: 160px; }
+.details { flex: 1; min-width: 0; }
+.prize { font-size: 1.4em; font-weight: bold; }
+code { word-break: break-all; background: #f0f0f0; padding: 0 0.2em; }
+button { font-size: 0.8em; margin-left: 0.5em; }
+table { width: 100%; border-collapse: collapse; }
+th, td { text-align: left; padding: 0.4em; border-bottom: 1px solid #ddd; vertical-align: top; }
+";
+
+const SCRIPT: &str = "
+document.querySelectorAll('button[data-copy]').forEach(function (button) {
+  button.addEventListener('click', function () {
+    navigator.clipboard.writeText(button.dataset.copy).then(function () {
+      button.textContent = 'Copied';
+      setTimeout(function () { button.textContent = 'Copy'; }, 1500);
+    });
+  });
+});
+";
+
+fn page(title: &str, generated_at: &str, active: &[SitePuzzle], solved: &[SitePuzzle]) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<p class=\"generated\">Updated {generated_at} · <a href=\"puzzles.json\">puzzles.json</a></p>\n",
+        title = escape(title),
+        generated_at = escape(generated_at),
+    );
+
+    let _ = writeln!(html, "<h2>🎯 Active puzzles</h2>");
+    if active.is_empty() {
+        let _ = writeln!(html, "<p>No active puzzles right now.</p>");
+    }
+    for puzzle in active {
+        let _ = writeln!(html, "<div class=\"card\">");
+        if let Some(qr) = &puzzle.qr {
+            let _ = writeln!(html, "<img src=\"{}\" alt=\"QR code of the puzzle address\">", escape(qr));
+        }
+        let _ = writeln!(html, "<div class=\"details\">");
+        let _ = writeln!(html, "<div class=\"prize\">💰 {} L-BTC</div>", puzzle.prize);
+        if puzzle.hints.is_empty() {
+            let _ = writeln!(html, "<p>💡 No hint yet</p>");
+        }
+        for hint in &puzzle.hints {
+            let _ = writeln!(html, "<p>💡 {}</p>", escape(hint));
+        }
+        let _ = writeln!(html, "<p>🔐 Target hash <code>{}</code></p>", escape(&puzzle.target_hash));
+        let _ = writeln!(
+            html,
+            "<p>📍 Address <code>{address}</code><button data-copy=\"{address}\">Copy</button></p>",
+            address = escape(&puzzle.address),
+        );
+        let _ = writeln!(
+            html,
+            "<p>🌐 {} · {}{}</p>",
+            escape(&puzzle.network),
+            escape(&puzzle.contract),
+            puzzle.created_at.as_deref().map(|c| format!(" · created {}", escape(c))).unwrap_or_default(),
+        );
+        if let Some(download) = &puzzle.download {
+            let _ = writeln!(html, "<p><a href=\"{}\" download>⬇️ Puzzle file</a></p>", escape(download));
+        }
+        let _ = writeln!(html, "</div>\n</div>");
+    }
+
+    let _ = writeln!(html, "<h2>🏆 Hall of fame</h2>");
+    if solved.is_empty() {
+        let _ = writeln!(html, "<p>No puzzle has been solved yet.</p>");
+    } else {
+        let _ = writeln!(
+            html,
+            "<table>\n<tr><th>Prize</th><th>Winning secret</th><th>Winner</th><th>Solve transaction</th><th>Block</th></tr>"
+        );
+        for puzzle in solved {
+            let Some(solve) = &puzzle.solve else {
+                continue;
+            };
+            let secret = match (&solve.secret_text, &solve.secret_hex) {
+                (Some(text), _) => format!("\"{}\"", escape(text)),
+                (None, Some(hex)) => format!("<code>0x{}</code>", escape(hex)),
+                (None, None) => "?".to_string(),
+            };
+            let download = puzzle
+                .download
+                .as_deref()
+                .map(|d| format!(" <a href=\"{}\" download>⬇️</a>", escape(d)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<tr><td>{} L-BTC{}</td><td>{}{}</td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                puzzle.prize,
+                download,
+                secret,
+                if solve.verified { "" } else { " (unverified)" },
+                winner(solve),
+                escape(&solve.txid),
+                solve.block_height.map(|h| h.to_string()).unwrap_or_else(|| "mempool".to_string()),
+            );
+        }
+        let _ = writeln!(html, "</table>");
+    }
+
+    let _ = write!(html, "<script>{SCRIPT}</script>\n</body>\n</html>\n");
+    html
+}
+
+/// The winner's nickname and address, or just the address.
+fn winner(solve: &SolveInfo) -> String {
+    let address = format!("<code>{}</code>", escape(solve.destination.as_deref().unwrap_or("?")));
+    match &solve.winner {
+        Some(nickname) => format!("<strong>{}</strong><br>{}", escape(nickname), address),
+        None => address,
+    }
+}
+
+/// Escape text for HTML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,68 @@
+/*
+ * REVEAL SECRET - Decrypts a puzzle_XXXX_SECRET.json file
+ *
+ * Usage:
+ *   puzzle-hunt reveal-secret --file <secret_file.json> [--identity <identity_file>]
+ *   puzzle-hunt reveal-secret --encrypt --file <secret_file.json>
+ *
+ * Examples:
+ *   puzzle-hunt reveal-secret --file puzzle_a0dc65ff_SECRET.json
+ *   puzzle-hunt reveal-secret --file puzzle_a0dc65ff_SECRET.json --identity ~/.age/puzzles.key
+ *   puzzle-hunt reveal-secret --encrypt --file puzzle_a0dc65ff_SECRET.json
+ *
+ * Passphrase files read PUZZLE_SECRET_PASSPHRASE or prompt for it.
+ * Files encrypted to an age recipient need the matching identity file.
+ *
+ * --encrypt upgrades a plaintext SECRET file written by an older
+ * create-puzzle, using the same key selection as create.
+ */
+
+use crate::output;
+use crate::progress;
+use crate::secret_file;
+use anyhow::Result;
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Args)]
+pub struct RevealSecretArgs {
+    /// SECRET file (puzzle_XXXX_SECRET.json)
+    #[arg(long)]
+    pub file: PathBuf,
+    /// age identity file, for SECRET files encrypted to a recipient
+    #[arg(long, conflicts_with = "encrypt")]
+    pub identity: Option<PathBuf>,
+    /// Encrypt a plaintext SECRET file in place instead
+    #[arg(long)]
+    pub encrypt: bool,
+}
+
+pub fn run(args: RevealSecretArgs) -> Result<serde_json::Value> {
+    if args.encrypt {
+        return encrypt_legacy(&args.file);
+    }
+
+    let private_info = secret_file::read_secret_file(&args.file, args.identity.as_deref())?;
+
+    eprintln!("🔓 Decrypted {}", args.file.display());
+    if !output::is_json() {
+        println!("{}", serde_json::to_string_pretty(&private_info)?);
+    }
+    Ok(private_info)
+}
+
+fn encrypt_legacy(path: &Path) -> Result<serde_json::Value> {
+    let data = std::fs::read_to_string(path)?;
+    let file: serde_json::Value = serde_json::from_str(&data)?;
+
+    if secret_file::is_encrypted(&file) {
+        progress!("✅ {} is already encrypted", path.display());
+        return Ok(serde_json::json!({ "file": path, "encrypted": true }));
+    }
+
+    let key = secret_file::encryption_key_from_env()?;
+    secret_file::write_encrypted(path, &file, &key)?;
+
+    progress!("🔒 Encrypted {} with {}", path.display(), key.describe());
+    Ok(serde_json::json!({ "file": path, "encrypted": true }))
+}
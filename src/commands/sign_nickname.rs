@@ -0,0 +1,85 @@
+/*
+ * SIGN NICKNAME - Sign a registration as a participant
+ *
+ * Usage:
+ *   puzzle-hunt sign-nickname --address <address> --nickname <nickname> [--key]
+ *
+ * Examples:
+ *   puzzle-hunt sign-nickname --address tex1q... --nickname alice
+ *   puzzle-hunt sign-nickname --address tex1q... --nickname alice --key
+ *   PUZZLE_NICKNAME_KEY=cV... puzzle-hunt sign-nickname --address tex1q... --nickname alice
+ *
+ * Signs the registration message (see registration.rs) with the key of
+ * the address you will solve to: the WIF in PUZZLE_NICKNAME_KEY, typed
+ * at a prompt with --key, or taken from the node wallet with
+ * dumpprivkey. The key is never a command-line argument, so it stays out
+ * of shell history and ps. The message carries the current
+ * time, so each signature is new and an older one cannot replace it.
+ * Send the address, nickname, time and signature to the organizer; the
+ * key never leaves this machine.
+ */
+
+use crate::chain::ElementsCli;
+use crate::progress;
+use crate::registration;
+use anyhow::{Context, Result};
+use clap::Args;
+use std::str::FromStr;
+
+/// Environment variable holding the private key (WIF) to sign with.
+pub const KEY_ENV: &str = "PUZZLE_NICKNAME_KEY";
+
+#[derive(Debug, Args)]
+pub struct SignNicknameArgs {
+    /// Address you will solve to
+    #[arg(long)]
+    pub address: String,
+    /// Nickname to register
+    #[arg(long)]
+    pub nickname: String,
+    /// Prompt for the private key of the address (WIF) instead of asking
+    /// the node wallet [default: PUZZLE_NICKNAME_KEY, then the node wallet]
+    #[arg(long)]
+    pub key: bool,
+}
+
+pub fn run(args: SignNicknameArgs) -> Result<serde_json::Value> {
+    registration::check_nickname(&args.nickname)?;
+    let address = registration::unconfidential(&args.address)?;
+
+    let wif = match std::env::var(KEY_ENV) {
+        Ok(key) => key,
+        Err(_) if args.key => {
+            rpassword::prompt_password("🔑 Private key (WIF): ").context("Failed to read the private key")?
+        }
+        Err(_) => ElementsCli::from_config()?
+            .call_wallet(&["dumpprivkey", &address])
+            .with_context(|| {
+                format!("The node wallet cannot give the key of this address; use --key or {}", KEY_ENV)
+            })?,
+    };
+    let key = bitcoin::PrivateKey::from_str(wif.trim()).context("Invalid private key")?;
+
+    let signed_at = registration::now();
+    let signature = registration::sign(&address, &args.nickname, signed_at, &key)?;
+    registration::verify(&address, &args.nickname, signed_at, &signature)
+        .context("The key does not match the address")?;
+
+    progress!("✍️  Signed: {}", registration::message(&address, &args.nickname, signed_at));
+    progress!();
+    progress!("Send this to the organizer:");
+    progress!(
+        "   --address {} --nickname \"{}\" --signed-at {} --signature {}",
+        address,
+        args.nickname,
+        signed_at,
+        signature
+    );
+
+    Ok(serde_json::json!({
+        "address": address,
+        "nickname": args.nickname,
+        "signed_at": signed_at,
+        "signature": signature,
+    }))
+}
@@ -0,0 +1,841 @@
+/*
+ * SOLVE - Solve a puzzle and claim its prize
+ *
+ * Usage:
+ *   puzzle-hunt solve --puzzle <puzzle_file.json> --secret <secret> --to <destination_address>
+ *
+ * Example:
+ *   puzzle-hunt solve --puzzle puzzle_2cf24dba.json --secret "satoshi" --to tex1q...
+ *
+ * This will:
+ * 1. Get UTXO info for the puzzle's txid:vout
+ * 2. Verify the secret matches the target hash
+ * 3. Create a transaction spending the puzzle UTXO
+ * 4. Provide the secret as witness
+ * 5. Broadcast and win the prize!
+ *
+ * --outpoint <txid:vout> spends another output paying the puzzle
+ * address, e.g. one made by add-to-pot, instead of the original one.
+ *
+ * --dry-run and --pset-out work without a node given the funding
+ * transaction (--funding-tx), a fixed fee (--fee-rate or --fee) and, on
+ * chains other than liquidv1 and liquidtestnet, --genesis-hash. Nothing
+ * then checks that the output is still unspent.
+ *
+ * The JSON result has the txid, fee and transaction hex; errors carry
+ * a code (wrong_secret, utxo_spent, broadcast_rejected, ...).
+ */
+
+use crate::amount::Amount;
+use crate::budget;
+use crate::chain::ElementsCli;
+use crate::contract::{self, PuzzleContract};
+use crate::execution::{self, SpendContext};
+use crate::fees::{self, FeePolicy};
+use crate::output::{self, ErrorCode};
+use crate::progress;
+use crate::pset;
+use crate::store::{self, PuzzleStore, SpendRecord};
+use crate::wallet;
+use anyhow::{Context, Result};
+use chrono;
+use clap::Args;
+use elements::pset::PartiallySignedTransaction as Psbt;
+use elements::{confidential, secp256k1_zkp as secp256k1, Address, OutPoint, TxIn, TxInWitness, TxOut, TxOutSecrets};
+use secp256k1::SecretKey;
+use std::str::FromStr;
+
+#[derive(Debug, Args)]
+pub struct SolveArgs {
+    /// Public puzzle file (puzzle_XXXX.json)
+    #[arg(long)]
+    pub puzzle: String,
+    /// The secret: text ("satoshi"), a hex number ("0x00000001") or hex bytes ("0xdeadbeef")
+    #[arg(long)]
+    pub secret: String,
+    /// Address receiving the prize (confidential for confidential puzzles)
+    #[arg(long)]
+    pub to: String,
+    /// Blinding key of a confidential puzzle [default: from the puzzle file]
+    #[arg(long)]
+    pub blinding_key: Option<String>,
+    /// Fee rate in sat/vB [default: estimatesmartfee]
+    #[arg(long, conflicts_with = "fee")]
+    pub fee_rate: Option<String>,
+    /// Absolute fee in sats
+    #[arg(long)]
+    pub fee: Option<String>,
+    /// Pay the fee from the node wallet so the whole prize is sent
+    #[arg(long, conflicts_with = "funding_tx")]
+    pub fee_from_wallet: bool,
+    /// Build and check the transaction without broadcasting it. The node
+    /// reads the puzzle output and runs testmempoolaccept; without one,
+    /// pass --funding-tx and --fee-rate or --fee
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Write the spend as a PSET for other wallets instead (see finalize-pset)
+    #[arg(long, value_name = "FILE")]
+    pub pset_out: Option<String>,
+    /// Spend this output of the puzzle address instead of the puzzle file's
+    #[arg(long, value_name = "TXID:VOUT")]
+    pub outpoint: Option<String>,
+    /// Hex of the funding transaction, to read the puzzle output without
+    /// a node (--dry-run or --pset-out only)
+    #[arg(long, value_name = "HEX")]
+    pub funding_tx: Option<String>,
+    /// Genesis block hash for the sighash [default: from the node, or
+    /// known for liquidv1 and liquidtestnet]
+    #[arg(long, value_name = "HASH")]
+    pub genesis_hash: Option<String>,
+}
+
+/// The puzzle UTXO being spent.
+struct PuzzleUtxo {
+    amount: Amount,
+    asset: String,
+    /// Unblinded value and blinding factors of a confidential puzzle
+    secrets: Option<TxOutSecrets>,
+    /// The output being spent, as the node sees it
+    txout: TxOut,
+}
+
+fn get_utxo_info(cli: &ElementsCli, txid: &str, vout: u32, blinding_key: Option<SecretKey>) -> Result<PuzzleUtxo> {
+    // First, try gettxout
+    let utxo_data = cli.get_tx_out(txid, vout)
+        .context("Failed to get UTXO info")?
+        .unwrap_or(serde_json::Value::Null);
+
+    // Check if UTXO exists
+    if utxo_data.is_null() {
+        return Err(output::error(
+            ErrorCode::UtxoSpent,
+            format!(
+                "❌ UTXO {}:{} not found or already spent!\n   The puzzle may have already been solved by someone else.",
+                txid, vout
+            ),
+        ));
+    }
+
+    // Explicit puzzles can be read directly; confidential ones need the
+    // blinding key published by the puzzle creator
+    let utxo = match (Amount::from_rpc(&utxo_data["value"]), utxo_data["asset"].as_str(), blinding_key) {
+        (Some(value), Some(asset), _) => PuzzleUtxo {
+            amount: value,
+            asset: asset.to_string(),
+            secrets: None,
+            txout: TxOut {
+                value: confidential::Value::Explicit(value.to_sat()),
+                script_pubkey: elements::Script::from(
+                    hex::decode(utxo_data["scriptPubKey"]["hex"].as_str().unwrap_or_default())?,
+                ),
+                asset: confidential::Asset::Explicit(elements::AssetId::from_str(asset)?),
+                nonce: confidential::Nonce::Null,
+                witness: elements::TxOutWitness::empty(),
+            },
+        },
+        (_, _, Some(blinding_key)) => {
+            let (txout, secrets) = crate::confidential::unblind_output(cli, txid, vout, blinding_key)?;
+            progress!("   ✓ Unblinded confidential puzzle output");
+            PuzzleUtxo {
+                amount: Amount::from_sat(secrets.value),
+                asset: secrets.asset.to_string(),
+                secrets: Some(secrets),
+                txout,
+            }
+        }
+        _ => {
+            return Err(output::error(
+                ErrorCode::MissingBlindingKey,
+                format!(
+                    "❌ UTXO {}:{} is confidential!\n   Pass the puzzle's blinding key with --blinding-key <hex>.",
+                    txid, vout
+                ),
+            ));
+        }
+    };
+
+    // Get confirmations if available
+    if let Some(confs) = utxo_data["confirmations"].as_u64() {
+        progress!("   ✓ UTXO has {} confirmations", confs);
+    }
+
+    Ok(utxo)
+}
+
+/// Read `txid:vout` from the raw funding transaction instead of the node.
+/// Whether it is still unspent is not checked.
+fn utxo_from_funding_tx(tx_hex: &str, txid: &str, vout: u32, blinding_key: Option<SecretKey>) -> Result<PuzzleUtxo> {
+    let tx: elements::Transaction = elements::encode::deserialize(
+        &hex::decode(tx_hex.trim()).map_err(|_| output::error(ErrorCode::InvalidArguments, "--funding-tx is not valid hex"))?,
+    )
+    .map_err(|e| output::error(ErrorCode::InvalidArguments, format!("Failed to decode --funding-tx: {}", e)))?;
+    if tx.txid().to_string() != txid {
+        return Err(output::error(
+            ErrorCode::InvalidArguments,
+            format!("--funding-tx is {}, not the puzzle's funding transaction {}", tx.txid(), txid),
+        ));
+    }
+    let txout = tx.output.get(vout as usize)
+        .ok_or_else(|| anyhow::anyhow!("Cannot find output {} in transaction", vout))?
+        .clone();
+
+    match (txout.value.explicit(), txout.asset.explicit(), blinding_key) {
+        (Some(value), Some(asset), _) => Ok(PuzzleUtxo {
+            amount: Amount::from_sat(value),
+            asset: asset.to_string(),
+            secrets: None,
+            txout,
+        }),
+        (_, _, Some(blinding_key)) => {
+            let (txout, secrets) = crate::confidential::unblind_tx_output(&tx, vout, blinding_key)?;
+            progress!("   ✓ Unblinded confidential puzzle output");
+            Ok(PuzzleUtxo {
+                amount: Amount::from_sat(secrets.value),
+                asset: secrets.asset.to_string(),
+                secrets: Some(secrets),
+                txout,
+            })
+        }
+        _ => Err(output::error(
+            ErrorCode::MissingBlindingKey,
+            format!(
+                "❌ UTXO {}:{} is confidential!\n   Pass the puzzle's blinding key with --blinding-key <hex>.",
+                txid, vout
+            ),
+        )),
+    }
+}
+
+/// The output paying `output_value` to the solver.
+fn prize_output(
+    output_value: Amount,
+    asset_id: elements::AssetId,
+    dest_addr: &Address,
+    secrets: Option<&TxOutSecrets>,
+) -> Result<TxOut> {
+    // A confidential input must be balanced by a blinded output
+    match secrets {
+        Some(secrets) => crate::confidential::blinded_output(output_value, asset_id, dest_addr, std::slice::from_ref(secrets)),
+        None => Ok(TxOut {
+            value: confidential::Value::Explicit(output_value.to_sat()),
+            script_pubkey: dest_addr.script_pubkey(),
+            asset: confidential::Asset::Explicit(asset_id),
+            nonce: confidential::Nonce::Null,
+            witness: elements::TxOutWitness::empty(),
+        }),
+    }
+}
+
+/// The prize output, paying the fee out of the prize, and the explicit fee output.
+fn prize_outputs(
+    value: Amount,
+    fee: Amount,
+    asset_id: elements::AssetId,
+    dest_addr: &Address,
+    secrets: Option<&TxOutSecrets>,
+) -> Result<(Amount, Vec<TxOut>)> {
+    let output_value = match value.checked_sub(fee) {
+        Some(output_value) if output_value > Amount::ZERO => output_value,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "UTXO value ({} sats) is too small to pay fee ({} sats)",
+                value.to_sat(), fee.to_sat()
+            ));
+        }
+    };
+
+    let destination_output = prize_output(output_value, asset_id, dest_addr, secrets)?;
+    Ok((output_value, vec![destination_output, TxOut::new_fee(fee.to_sat(), asset_id)]))
+}
+
+/// Print the decoded transaction and ask the node whether it would
+/// accept it, without broadcasting. Returns the testmempoolaccept entry.
+fn dry_run_report(cli: &ElementsCli, tx_hex: &str) -> Result<serde_json::Value> {
+    progress!("🧪 Dry run: the transaction will NOT be broadcast");
+    progress!();
+
+    match cli.call_json(&["decoderawtransaction", tx_hex]) {
+        Ok(decoded) => {
+            progress!("📜 Decoded transaction:");
+            progress!("{}", serde_json::to_string_pretty(&decoded)?);
+        }
+        Err(e) => progress!("   ⚠️  Could not decode with the node: {}", e),
+    }
+    progress!();
+
+    progress!("🔍 testmempoolaccept:");
+    let result = match cli.call_json(&["testmempoolaccept", &serde_json::json!([tx_hex]).to_string()]) {
+        Ok(result) => result,
+        Err(e) => {
+            progress!("   ⚠️  Node check unavailable: {}", e);
+            serde_json::Value::Null
+        }
+    };
+    let entry = &result[0];
+    if entry.is_null() {
+        progress!("   Skipped");
+    } else if entry["allowed"].as_bool() == Some(true) {
+        progress!("   ✅ The node would accept this transaction");
+        if let Some(vsize) = entry["vsize"].as_u64() {
+            progress!("   📏 Node vsize: {} vB", vsize);
+        }
+    } else {
+        progress!(
+            "   ❌ Rejected: {}",
+            entry["reject-reason"].as_str().unwrap_or("unknown reason")
+        );
+    }
+    progress!();
+
+    progress!("🔤 Transaction hex:");
+    progress!("{}", tx_hex);
+    progress!();
+    progress!("   Broadcast it later with: ./elements-cli sendrawtransaction <hex>");
+    Ok(entry.clone())
+}
+
+/// Record the puzzle, so puzzle-watcher follows it, and the signed spend.
+fn save_spend(puzzle_file: &str, spend: &SpendRecord) -> Result<PuzzleStore> {
+    let db = PuzzleStore::open_default()?;
+    db.import_json_file(std::path::Path::new(puzzle_file))?;
+    db.record_spend(spend)?;
+    Ok(db)
+}
+
+pub fn run(args: SolveArgs) -> Result<serde_json::Value> {
+    let fee_policy = FeePolicy::from_args(args.fee_rate.as_deref(), args.fee.as_deref())?;
+    let fee_from_wallet = args.fee_from_wallet;
+    let dry_run = args.dry_run;
+    let pset_out = args.pset_out.clone();
+    if args.funding_tx.is_some() {
+        if !dry_run && pset_out.is_none() {
+            return Err(output::error(
+                ErrorCode::InvalidArguments,
+                "--funding-tx cannot tell whether the puzzle is still unspent; use it with --dry-run or --pset-out",
+            ));
+        }
+        if let FeePolicy::Estimate { .. } = fee_policy {
+            return Err(output::error(ErrorCode::InvalidArguments, "--funding-tx needs --fee-rate or --fee"));
+        }
+    }
+    let genesis_hash = args.genesis_hash
+        .as_deref()
+        .map(|hash| {
+            elements::BlockHash::from_str(hash)
+                .map_err(|_| output::error(ErrorCode::InvalidArguments, format!("Invalid --genesis-hash {}", hash)))
+        })
+        .transpose()?;
+
+    let puzzle_file = &args.puzzle;
+    let secret = &args.secret;
+    let dest_address = &args.to;
+
+    progress!("╔══════════════════════════════════════╗");
+    progress!("║      🎯 SOLVING PUZZLE HUNT 🎯       ║");
+    progress!("╚══════════════════════════════════════╝");
+    progress!();
+    progress!("🎮 Starting puzzle solver...");
+    progress!("📅 Time: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+    progress!();
+
+    // Read puzzle info from JSON
+    progress!("📂 Step 1: Loading puzzle information");
+    progress!("   File: {}", puzzle_file);
+    let puzzle_data = std::fs::read_to_string(puzzle_file)
+        .context("Failed to read puzzle file")?;
+    let puzzle: serde_json::Value = serde_json::from_str(&puzzle_data)
+        .context("Failed to parse puzzle JSON")?;
+
+    let (txid_str, vout) = match &args.outpoint {
+        Some(outpoint) => {
+            let (txid, vout) = outpoint
+                .split_once(':')
+                .ok_or_else(|| output::error(ErrorCode::InvalidArguments, "--outpoint must be <txid>:<vout>"))?;
+            let vout = vout
+                .parse()
+                .map_err(|_| output::error(ErrorCode::InvalidArguments, format!("Invalid vout in {}", outpoint)))?;
+            (txid, vout)
+        }
+        None => {
+            let txid = puzzle["txid"].as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing txid in puzzle file"))?;
+            let vout = puzzle["vout"].as_u64()
+                .ok_or_else(|| anyhow::anyhow!("Missing vout in puzzle file"))? as u32;
+            (txid, vout)
+        }
+    };
+    let target_hash = puzzle["target_hash"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing target_hash in puzzle file"))?;
+    let puzzle_address = puzzle["address"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing address in puzzle file"))?;
+    let hint = puzzle["hint"].as_str().unwrap_or("No hint provided");
+    let puzzle_amount = puzzle["amount"].as_str().unwrap_or("Unknown");
+    let blinding_key = args.blinding_key
+        .as_deref()
+        .or(puzzle["blinding_key"].as_str())
+        .map(crate::confidential::parse_blinding_key)
+        .transpose()?;
+
+    progress!("✅ Puzzle loaded successfully!");
+    progress!("   📍 Puzzle address: {}", puzzle_address);
+    progress!("   📝 Transaction ID: {}", txid_str);
+    progress!("   🔢 Output index: {}", vout);
+    progress!("   🎯 Target hash: {}", target_hash);
+    progress!("   💰 Original amount: {} L-BTC", puzzle_amount);
+    progress!("   💡 Hint: \"{}\"", hint);
+    for released in puzzle["hints"].as_array().into_iter().flatten() {
+        progress!("   💡 Hint: \"{}\"", released["hint"].as_str().unwrap_or_default());
+    }
+    for locked in puzzle["locked_hints"].as_array().into_iter().flatten() {
+        match locked["hint"].as_str() {
+            Some(hint) => progress!("   🔓 Hint: \"{}\"", hint),
+            None => progress!("   🔒 Locked hint (puzzle-hunt unlock-hints)"),
+        }
+    }
+    progress!();
+
+    // 1. Get UTXO information FIRST (we need the value for the hash!)
+    progress!("📊 Step 2: Fetching UTXO information from blockchain");
+    progress!("   Connecting to Elements daemon...");
+
+    let cli = ElementsCli::from_config()?;
+    let utxo = match &args.funding_tx {
+        Some(tx_hex) => {
+            progress!("   Reading the output from --funding-tx (unspent status not checked)");
+            utxo_from_funding_tx(tx_hex, txid_str, vout, blinding_key)?
+        }
+        None => get_utxo_info(&cli, txid_str, vout, blinding_key)?,
+    };
+    let asset_id_str = utxo.asset.clone();
+    let value = utxo.amount;
+    if fee_from_wallet && utxo.secrets.is_some() {
+        return Err(output::error(
+            ErrorCode::InvalidArguments,
+            "❌ --fee-from-wallet needs an explicit puzzle output; the wallet cannot blind against a confidential input",
+        ));
+    }
+
+    if args.funding_tx.is_some() {
+        progress!("✅ UTXO read from the funding transaction!");
+    } else {
+        progress!("✅ UTXO verified on-chain!");
+    }
+    progress!("   💰 Current prize: {} L-BTC", value);
+    progress!("   💵 In satoshis: {} sats", value.to_sat());
+    progress!("   🪙 Asset ID: {}", &asset_id_str[..16]);
+    progress!("   📈 Full Asset: {}", asset_id_str);
+    progress!();
+
+    // 2. Verify the secret is correct WITH THE CURRENT VALUE
+    progress!("🔐 Step 3: Processing and verifying your secret");
+    progress!("   Your secret: \"{}\"", secret);
+
+    // Text and hex alike are right-aligned in 32 bytes
+    let secret_bytes = contract::parse_secret(secret)?;
+    match secret.strip_prefix("0x").or_else(|| secret.strip_prefix("0X")) {
+        Some(hex_str) => {
+            progress!("   🔤 Format detected: Hex");
+            progress!("      Length: {} bytes", hex_str.len() / 2);
+            progress!("      Hex: 0x{}", hex_str);
+        }
+        None => {
+            progress!("   📝 Format detected: Text string");
+            progress!("      Length: {} characters", secret.len());
+            progress!("      ASCII bytes: {:?}", &secret.as_bytes()[..secret.len().min(8)]);
+        }
+    }
+    progress!();
+    progress!("🧮 Step 4: Computing hash");
+    progress!("   Hash formula: SHA256(secret)");
+    progress!();
+    progress!("   📥 Hash input:");
+    progress!("      Secret (32 bytes): 0x{}", hex::encode(secret_bytes));
+
+    let hash = contract::hash_secret(&secret_bytes);
+    let hash_hex = format!("0x{}", hex::encode(hash));
+
+    progress!();
+    progress!("   🔄 Computing: SHA256(secret)");
+    progress!("   📤 Result: {}", hash_hex);
+    progress!();
+    progress!("🔍 Step 5: Verifying hash matches target");
+    progress!("   Expected: {}", target_hash);
+    progress!("   Computed: {}", hash_hex);
+
+    if hash_hex != target_hash {
+        progress!();
+        progress!("╔══════════════════════════════════════════════╗");
+        progress!("║         ❌ VERIFICATION FAILED! ❌            ║");
+        progress!("╚══════════════════════════════════════════════╝");
+        eprintln!();
+        eprintln!("🚫 The computed hash does not match the target!");
+        eprintln!();
+        eprintln!("📊 Debug information:");
+        eprintln!("   Expected hash: {}", target_hash);
+        eprintln!("   Your hash:     {}", hash_hex);
+        eprintln!("   Your secret:   \"{}\"", secret);
+        eprintln!();
+        eprintln!("⚠️  Possible reasons for failure:");
+        eprintln!("   1. ❌ Wrong secret - check your spelling");
+        eprintln!("   2. 📝 Check if the secret format is correct");
+        eprintln!("   3. 🔤 Try different formats (string vs hex)");
+        eprintln!();
+        eprintln!("💡 Tips:");
+        eprintln!("   - The secret is case-sensitive");
+        eprintln!("   - Try different secret formats (string vs hex)");
+        eprintln!("   - Contact the puzzle creator if stuck");
+        return Err(output::error(
+            ErrorCode::WrongSecret,
+            format!("SHA256 of the secret is {}, expected {}", hash_hex, target_hash),
+        ));
+    }
+
+    progress!();
+    progress!("╔══════════════════════════════════════════════╗");
+    progress!("║       ✅ SECRET VERIFIED SUCCESSFULLY! ✅     ║");
+    progress!("╚══════════════════════════════════════════════╝");
+    progress!();
+
+    // 3. Compile the contract
+    progress!("🛠️ Step 6: Compiling Simplicity smart contract");
+    progress!("   Contract: puzzle_jackpot.simf");
+    progress!("   Preparing contract parameters...");
+
+    progress!("   Compiling with TARGET_HASH parameter...");
+    let contract = PuzzleContract::compile(hash)?;
+
+    let cmr = contract.cmr();
+    progress!("✅ Contract compiled successfully!");
+    progress!("   📝 CMR (Commitment Merkle Root): 0x{}", hex::encode(cmr.as_ref()));
+    progress!();
+
+    // 4. Create spending transaction
+    progress!("💸 Step 7: Creating spending transaction");
+    progress!("   Parsing destination address...");
+
+    let dest_addr = Address::from_str(dest_address)?;
+    progress!("   📊 Input amount: {} sats ({} L-BTC)", value.to_sat(), value);
+    progress!("   📍 Destination:  {}", dest_address);
+    if utxo.secrets.is_some() {
+        progress!("   🙈 Prize output will be blinded (range and surjection proofs)");
+    }
+    progress!();
+
+    let txid = elements::Txid::from_str(txid_str)?;
+    let outpoint = OutPoint::new(txid, vout);
+
+    let asset_id = elements::AssetId::from_str(&asset_id_str)?;
+
+    // Draft outputs to measure the transaction; the fee is set in step 11
+    let draft_outputs = if fee_from_wallet {
+        // The whole prize goes out; the wallet adds change and fee outputs
+        vec![prize_output(value, asset_id, &dest_addr, None)?]
+    } else {
+        let draft_fee = match fee_policy {
+            FeePolicy::Fixed(fee) => fee,
+            _ => Amount::from_sat(1),
+        };
+        prize_outputs(value, draft_fee, asset_id, &dest_addr, utxo.secrets.as_ref())?.1
+    };
+
+    let psbt = Psbt::from_tx(elements::Transaction {
+        version: 2,
+        lock_time: elements::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: outpoint,
+            is_pegin: false,
+            script_sig: elements::Script::new(),
+            // Signal RBF so the spend can be fee-bumped in a race
+            sequence: elements::Sequence::ENABLE_RBF_NO_LOCKTIME,
+            asset_issuance: elements::AssetIssuance::null(),
+            witness: TxInWitness::empty(),
+        }],
+        output: draft_outputs,
+    });
+
+    // 5. Create witness with the secret
+    progress!("🔐 Step 8: Creating witness data with your secret");
+    progress!("   SECRET witness: 0x{}", hex::encode(secret_bytes));
+
+    // 6. Satisfy the program and create final witness
+    progress!();
+    progress!("🔓 Step 9: Satisfying the Simplicity program");
+    progress!("   Running contract with witness data...");
+    let satisfied = contract.satisfy(secret_bytes)?;
+
+    let (program_bytes, witness_bytes) = satisfied.redeem().to_vec_with_witness();
+    progress!("✅ Program satisfied successfully!");
+    progress!("   📏 Program size: {} bytes", program_bytes.len());
+    progress!("   📏 Witness size: {} bytes", witness_bytes.len());
+    progress!("   📏 Total script: {} bytes", program_bytes.len() + witness_bytes.len());
+    progress!();
+
+    // 7. Add witness to transaction
+    progress!("🔧 Step 10: Building Taproot witness structure");
+    progress!("   Creating Taproot script tree...");
+    let internal_key = contract.spend_info.internal_key();
+    progress!("   Using unspendable internal key");
+    let script = &contract.script;
+    progress!("   Leaf version: 0x{:02x}", contract.leaf_version.as_u8());
+    let control_block = contract.control_block();
+    progress!("   Control block created ({} bytes)", control_block.serialize().len());
+
+    // Build the final witness
+    progress!();
+    progress!("   📦 Assembling final witness stack:");
+    progress!("      1. Witness data: {} bytes", witness_bytes.len());
+    progress!("      2. Program code: {} bytes", program_bytes.len());
+    progress!("      3. Script: {} bytes", script.as_bytes().len());
+    progress!("      4. Control block: {} bytes", control_block.serialize().len());
+
+    let mut witness_stack = vec![
+        witness_bytes,
+        program_bytes,
+        script.as_bytes().to_vec(),
+        control_block.serialize(),
+    ];
+
+    let total_witness_size: usize = witness_stack.iter().map(|v| v.len()).sum();
+    progress!("   📊 Total witness size: {} bytes", total_witness_size);
+
+    // The witness weight pays for the program's cost; pad with an annex if it falls short
+    progress!();
+    progress!("   🧮 Cost budget:");
+    budget::report(satisfied.redeem(), &witness_stack).print();
+    let annex = match budget::pad_witness(satisfied.redeem(), &mut witness_stack) {
+        Some(bytes) => {
+            progress!("   📎 Added {} byte annex to the witness", bytes);
+            witness_stack.last().cloned()
+        }
+        None => None,
+    };
+    if !budget::report(satisfied.redeem(), &witness_stack).within_budget {
+        return Err(anyhow::anyhow!("Program cost does not fit the witness budget even with an annex"));
+    }
+
+    let mut tx = psbt.extract_tx()
+        .map_err(|e| anyhow::anyhow!("Failed to extract transaction: {:?}", e))?;
+    tx.input[0].witness = TxInWitness {
+        script_witness: witness_stack,
+        pegin_witness: vec![],
+        amount_rangeproof: None,
+        inflation_keys_rangeproof: None,
+    };
+    progress!("✅ Transaction witness attached");
+    progress!();
+
+    // 8. Size the transaction (witness included) and set the fee
+    progress!("⚖️  Step 11: Estimating fee");
+    let mut spent_utxos = vec![utxo.txout.clone()];
+    let (output_value, quote) = if fee_from_wallet {
+        let mut sizing = tx.clone();
+        sizing.output.push(TxOut::new_fee(0, asset_id));
+        let quote = fee_policy.quote(&cli, fees::discount_vsize(&wallet::draft_with_fee_input(&sizing, asset_id)))?;
+
+        progress!("   👛 Paying fee from wallet '{}'", cli.wallet());
+        let fee_input = wallet::select_fee_input(&cli, asset_id, quote.fee)?;
+        progress!("      Fee input: {} ({} L-BTC)", fee_input.outpoint, fee_input.amount);
+
+        progress!("      Signing wallet input with walletprocesspsbt...");
+        tx = wallet::pay_fee_from_wallet(&cli, &tx, utxo.txout.clone(), &fee_input, quote.fee, asset_id)?;
+        spent_utxos.push(fee_input.utxo.clone());
+        (value, quote)
+    } else {
+        let quote = fee_policy.quote(&cli, fees::discount_vsize(&tx))?;
+        let (output_value, outputs) = prize_outputs(value, quote.fee, asset_id, &dest_addr, utxo.secrets.as_ref())?;
+        tx.output = outputs;
+        (output_value, quote)
+    };
+    let fee = quote.fee;
+    let vsize = fees::discount_vsize(&tx);
+
+    progress!("   📏 Discounted size: {} vB (weight {})", vsize, tx.weight());
+    progress!("   📈 Fee rate: {:.2} sat/vB from {}", quote.rate, quote.source);
+    progress!("   📊 Transaction economics:");
+    progress!("      Input amount:  {} sats ({} L-BTC)", value.to_sat(), value);
+    progress!("      Output amount: {} sats ({} L-BTC)", output_value.to_sat(), output_value);
+    progress!("      Network fee:   {} sats ({} L-BTC)", fee.to_sat(), fee);
+    progress!("      Effective rate: {:.2} sat/vB", fees::effective_rate(fee, vsize));
+    progress!();
+
+    // 9. Run the program locally, as the node will
+    progress!("🧪 Step 12: Running the Simplicity program locally");
+    let context = SpendContext {
+        tx: &tx,
+        spent_utxos: &spent_utxos,
+        input_index: 0,
+        script_cmr: cmr,
+        control_block: &control_block,
+        annex,
+        genesis_hash: match genesis_hash {
+            Some(hash) => hash,
+            None => execution::genesis_hash(&cli)?,
+        },
+    };
+    match execution::run(satisfied.redeem(), &context) {
+        Ok(()) => progress!("✅ Program executed successfully on the Bit Machine"),
+        Err(mut failure) => {
+            // Name the failing call in the contract source
+            let located = contract.satisfy_with_debug_symbols(secret_bytes).ok().and_then(|debug| {
+                execution::locate(&debug, contract::PUZZLE_CONTRACT_FILE, contract::PUZZLE_CONTRACT, &context)
+            });
+            if located.is_some() {
+                failure.explanation = located;
+            }
+            progress!();
+            progress!("╔══════════════════════════════════════════════╗");
+            progress!("║      ❌ LOCAL EXECUTION FAILED! ❌           ║");
+            progress!("╚══════════════════════════════════════════════╝");
+            eprintln!();
+            eprintln!("🚫 The node would reject this spend: {}", failure);
+            if !dry_run {
+                eprintln!("   Nothing was broadcast. Re-run with --dry-run to inspect the transaction.");
+                return Err(output::error(
+                    ErrorCode::ExecutionFailed,
+                    format!("Simplicity program failed: {}", failure),
+                ));
+            }
+        }
+    }
+    progress!();
+
+    // What --output json reports
+    let tx_hex = hex::encode(elements::encode::serialize(&tx));
+    let mut result = serde_json::json!({
+        "address": puzzle_address,
+        "puzzle_txid": txid_str,
+        "vout": vout,
+        "cmr": hex::encode(cmr.as_ref()),
+        "destination": dest_address,
+        "prize_sats": output_value,
+        "fee_sats": fee,
+        "fee_rate": fees::effective_rate(fee, vsize),
+        "vsize": vsize,
+        "txid": tx.txid().to_string(),
+        "tx_hex": &tx_hex,
+        "broadcast": false,
+    });
+
+    // Hand the spend to other wallets instead of broadcasting it
+    if let Some(path) = &pset_out {
+        progress!("📝 Step 13: Writing PSET");
+        let pset = pset::puzzle_pset(&tx, &spent_utxos, script, &control_block, internal_key);
+        std::fs::write(path, wallet::encode_pset(&pset))
+            .with_context(|| format!("Failed to write PSET to {}", path))?;
+        progress!("✅ PSET written to {}", path);
+        progress!("   Puzzle input finalized with the Simplicity witness; nothing was broadcast");
+        progress!();
+        progress!("🔍 Next steps:");
+        progress!("   1. Add inputs/outputs and sign them with your wallet (e.g. to pay the fee)");
+        progress!("   2. Broadcast with: cargo run --bin finalize-pset -- {}", path);
+        result["pset_file"] = path.as_str().into();
+        return Ok(result);
+    }
+
+    // 10. Broadcast transaction
+    if dry_run {
+        progress!("📡 Step 13: Checking transaction (dry run)");
+    } else {
+        progress!("📡 Step 13: Broadcasting transaction to the network");
+    }
+    let tx_size = elements::encode::serialize(&tx).len();
+    progress!("   📦 Transaction size: {} bytes", tx_size);
+
+    progress!("   🔤 Transaction hex: {}...{}", &tx_hex[..16], &tx_hex[tx_hex.len()-16..]);
+    progress!();
+
+    if dry_run {
+        result["dry_run"] = true.into();
+        result["mempool_accept"] = dry_run_report(&cli, &tx_hex)?;
+        return Ok(result);
+    }
+
+    // Keep the signed spend so it can be rebroadcast or fee-bumped
+    let spend = SpendRecord {
+        funding_txid: txid_str.to_string(),
+        funding_vout: vout,
+        txid: tx.txid().to_string(),
+        tx_hex: tx_hex.clone(),
+        fee,
+        vsize,
+        destination: Some(dest_address.to_string()),
+        status: store::SPEND_BUILT.to_string(),
+        replaces: None,
+        created_at: String::new(),
+    };
+    let db = match save_spend(puzzle_file, &spend) {
+        Ok(db) => Some(db),
+        Err(e) => {
+            eprintln!("   ⚠️  Could not save the signed spend in {}: {}", PuzzleStore::default_path().display(), e);
+            None
+        }
+    };
+    progress!("   💾 Signed spend saved (replaceable, bump with: cargo run --bin bump-fee -- {})", spend.txid);
+    progress!();
+    progress!("   🌐 Connecting to Elements daemon...");
+    progress!("   📤 Sending transaction to network...");
+
+    let broadcast_result = cli.call(&["sendrawtransaction", &tx_hex]);
+    if let Some(db) = &db {
+        let status = if broadcast_result.is_ok() { store::SPEND_BROADCAST } else { store::SPEND_FAILED };
+        if let Err(e) = db.set_spend_status(&spend.txid, status) {
+            eprintln!("   ⚠️  Could not update the saved spend: {}", e);
+        }
+    }
+
+    let broadcast_txid = match broadcast_result {
+        Ok(txid) => txid,
+        Err(error) => {
+            progress!();
+            progress!("╔══════════════════════════════════════════════╗");
+            progress!("║         ❌ BROADCAST FAILED! ❌              ║");
+            progress!("╚══════════════════════════════════════════════╝");
+            eprintln!();
+            eprintln!("🚫 Failed to broadcast transaction!");
+            eprintln!();
+            eprintln!("Error message: {}", error);
+            eprintln!();
+            eprintln!("Possible reasons:");
+            eprintln!("   - Network connectivity issues");
+            eprintln!("   - Transaction already in mempool");
+            eprintln!("   - Invalid witness data");
+            eprintln!("   - UTXO already spent (someone else won!)");
+            return Err(output::error(
+                ErrorCode::BroadcastRejected,
+                format!("Failed to broadcast transaction: {:#}", error),
+            ));
+        }
+    };
+
+    progress!();
+    progress!("╔══════════════════════════════════════════════╗");
+    progress!("║                                              ║");
+    progress!("║        🎉🎉🎉 SUCCESS! 🎉🎉🎉              ║");
+    progress!("║                                              ║");
+    progress!("║         YOU WON THE PUZZLE!                  ║");
+    progress!("║                                              ║");
+    progress!("╚══════════════════════════════════════════════╝");
+    progress!();
+    progress!("✅ Transaction successfully broadcasted!");
+    progress!();
+    progress!("📊 Transaction Details:");
+    progress!("   🆔 TXID: {}", broadcast_txid);
+    progress!("   💰 Prize sent to: {}", dest_address);
+    progress!("   💵 Amount: {} sats", output_value.to_sat());
+    progress!("   💸 In L-BTC: {} L-BTC", output_value);
+    progress!("   ⏱️  Time: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+    progress!();
+    progress!("🔍 Next steps:");
+    progress!("   1. Wait for confirmation (usually 1-2 minutes)");
+    progress!("   2. Check your wallet balance");
+    progress!("   3. View transaction details:");
+    progress!("      ./elements-cli getrawtransaction {} true", broadcast_txid);
+    progress!();
+    progress!("🏆 Congratulations on solving the puzzle hunt!");
+    progress!("   Share your victory with #SimplicityCTF");
+    progress!();
+
+    result["txid"] = broadcast_txid.into();
+    result["broadcast"] = true.into();
+    Ok(result)
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+/*
+ * UNLOCK HINTS - Decrypt locked hints whose key is public
+ *
+ * Usage:
+ *   puzzle-hunt unlock-hints --puzzle <puzzle_file.json> [--secret <secret> ...]
+ *
+ * Examples:
+ *   puzzle-hunt unlock-hints --puzzle puzzle_7d1a54a1.json
+ *   puzzle-hunt unlock-hints --puzzle puzzle_7d1a54a1.json --secret "satoshi"
+ *
+ * Anyone can run this; no SECRET file is involved. A hint locked behind
+ * another puzzle opens with that puzzle's secret, given with --secret
+ * or taken from its verified solve in the puzzle database. The
+ * unlocked hints are written back into the puzzle file (see
+ * locked_hints.rs).
+ */
+
+use crate::contract;
+use crate::locked_hints::{self, Unlock};
+use crate::progress;
+use crate::store::PuzzleStore;
+use anyhow::Result;
+use clap::Args;
+use std::path::Path;
+
+#[derive(Debug, Args)]
+pub struct UnlockHintsArgs {
+    /// Public puzzle file (puzzle_XXXX.json)
+    #[arg(long)]
+    pub puzzle: String,
+    /// Secret of an earlier puzzle, as text or 0x hex (repeatable)
+    #[arg(long)]
+    pub secret: Vec<String>,
+}
+
+pub fn run(args: UnlockHintsArgs) -> Result<serde_json::Value> {
+    let secrets = args
+        .secret
+        .iter()
+        .map(|secret| {
+            let bytes = contract::parse_secret(secret)?;
+            Ok((bytes, format!("0x{}", hex::encode(contract::hash_secret(&bytes)))))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The database is optional: it only adds secrets of solved puzzles
+    let db = PuzzleStore::open_default().ok();
+    let unlocked = locked_hints::unlock(Path::new(&args.puzzle), &secrets, db.as_ref())?;
+
+    if unlocked.is_empty() {
+        progress!("🔒 No locked hint can be opened yet");
+    }
+    for hint in &unlocked {
+        let Unlock::Solved(target_hash) = &hint.unlock;
+        progress!("🔓 Unlocked by solve of {}: \"{}\"", target_hash, hint.hint.as_deref().unwrap_or_default());
+    }
+
+    Ok(serde_json::json!({ "unlocked": unlocked }))
+}
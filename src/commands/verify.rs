@@ -0,0 +1,117 @@
+/*
+ * VERIFY - Check a puzzle file against its contract and the chain
+ *
+ * Usage:
+ *   puzzle-hunt verify --puzzle <puzzle_file.json> [--secret <secret>]
+ *
+ * Examples:
+ *   puzzle-hunt verify --puzzle puzzle_2cf24dba.json
+ *   puzzle-hunt verify --puzzle puzzle_2cf24dba.json --secret "satoshi"
+ *
+ * This will:
+ * 1. Recompile puzzle_jackpot.simf with the file's target hash and check
+ *    that the address really belongs to that contract
+ * 2. Look up the puzzle outpoint: still unspent, and paying the address
+ * 3. With --secret, check the secret against the target hash offline
+ *
+ * Nothing is spent. Participants can run it before trusting a puzzle
+ * file; a wrong address fails with contract_mismatch.
+ */
+
+use crate::chain::{self, ElementsCli};
+use crate::contract::{self, PuzzleContract};
+use crate::output::{self, ErrorCode};
+use crate::progress;
+use anyhow::{Context, Result};
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Public puzzle file (puzzle_XXXX.json)
+    #[arg(long)]
+    pub puzzle: String,
+    /// Check this secret against the target hash (text or 0x hex)
+    #[arg(long)]
+    pub secret: Option<String>,
+}
+
+pub fn run(args: VerifyArgs) -> Result<serde_json::Value> {
+    let puzzle: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&args.puzzle).context("Failed to read puzzle file")?,
+    )
+    .context("Failed to parse puzzle JSON")?;
+
+    let target_hash = puzzle["target_hash"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing target_hash in puzzle file"))?;
+    let address = puzzle["address"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing address in puzzle file"))?;
+    let network = puzzle["network"].as_str().unwrap_or("liquidtestnet");
+
+    progress!("🔍 Verifying {}", args.puzzle);
+
+    // 1. The address must be the contract's
+    let contract = PuzzleContract::compile(contract::parse_target_hash(target_hash)?)?;
+    let expected = contract.address(None, chain::address_params_for(network)).to_string();
+    let cmr = hex::encode(contract.cmr().as_ref());
+    progress!("   📝 CMR: 0x{}", cmr);
+    if expected != address {
+        return Err(output::error(
+            ErrorCode::ContractMismatch,
+            format!("❌ Address {} is not the puzzle contract for {} (expected {})", address, target_hash, expected),
+        ));
+    }
+    progress!("   ✅ Address matches the contract for {}", target_hash);
+
+    // 2. The outpoint, if the node can tell
+    let txid = puzzle["txid"].as_str().unwrap_or_default();
+    let vout = puzzle["vout"].as_u64().unwrap_or(0) as u32;
+    let cli = ElementsCli::from_config()?;
+    let onchain = if !cli.is_available() {
+        progress!("   ⚠️  Elements node not available, outpoint not checked");
+        "unknown"
+    } else {
+        match cli.get_tx_out(txid, vout)? {
+            Some(utxo) => {
+                let script_hex = hex::encode(contract.address(None, cli.address_params()).script_pubkey().as_bytes());
+                if utxo["scriptPubKey"]["hex"].as_str() != Some(script_hex.as_str()) {
+                    return Err(output::error(
+                        ErrorCode::ContractMismatch,
+                        format!("❌ Outpoint {}:{} does not pay the puzzle address", txid, vout),
+                    ));
+                }
+                progress!("   ✅ Outpoint {}:{} is unspent", txid, vout);
+                "unspent"
+            }
+            None => {
+                progress!("   🏁 Outpoint {}:{} is spent (or unknown to the node)", txid, vout);
+                "spent"
+            }
+        }
+    };
+
+    // 3. The secret, offline
+    let secret_matches = match &args.secret {
+        Some(secret) => {
+            let hash = contract::hash_secret(&contract::parse_secret(secret)?);
+            if hash != contract.target_hash {
+                return Err(output::error(
+                    ErrorCode::WrongSecret,
+                    format!("SHA256 of the secret is 0x{}, expected {}", hex::encode(hash), target_hash),
+                ));
+            }
+            progress!("   ✅ Secret hashes to the target hash");
+            Some(true)
+        }
+        None => None,
+    };
+
+    Ok(serde_json::json!({
+        "address": address,
+        "target_hash": target_hash,
+        "cmr": cmr,
+        "txid": txid,
+        "vout": vout,
+        "onchain": onchain,
+        "secret_matches": secret_matches,
+    }))
+}
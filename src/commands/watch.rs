@@ -0,0 +1,117 @@
+/*
+ * WATCH - Long-running chain watcher for puzzle lifecycle events
+ *
+ * Usage:
+ *   puzzle-hunt watch [--interval <secs>] [--webhook <url>] [--hook <command>] [--once]
+ *                     [--auto-bump [--max-fee-rate <sat/vB>]] [--release-hints [--identity <file>]]
+ *                     [--unlock-hints]
+ *
+ * Examples:
+ *   puzzle-hunt watch
+ *   puzzle-hunt watch --interval 10 --webhook http://127.0.0.1:8080/events
+ *   puzzle-hunt watch --hook 'notify-send "Puzzle $PUZZLE_EVENT"'
+ *
+ * Events are printed as JSON lines on stdout and stored in the puzzle
+ * database. Progress and errors go to stderr.
+ *
+ * --auto-bump outbids competing mempool spends of puzzles we are
+ * solving (spends saved by solve), --release-hints publishes scheduled
+ * hints as they come due (needs PUZZLE_SECRET_PASSPHRASE or --identity)
+ * and --unlock-hints decrypts hints locked behind a puzzle as soon as
+ * its solve is seen.
+ */
+
+use crate::chain::ElementsCli;
+use crate::hints::HintReleaser;
+use crate::locked_hints::HintUnlockSink;
+use crate::rbf::AutoBumpSink;
+use crate::store::PuzzleStore;
+use crate::watcher::{EventSink, HookSink, StdoutSink, Watcher, WebhookSink};
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Seconds between polls
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    pub interval: u64,
+    /// Also POST each event to a local http:// endpoint (repeatable)
+    #[arg(long, value_name = "URL")]
+    pub webhook: Vec<String>,
+    /// Also run a shell command per event, with the event as JSON on stdin (repeatable)
+    #[arg(long, value_name = "COMMAND")]
+    pub hook: Vec<String>,
+    /// Poll twice (learn state, then report) and exit
+    #[arg(long)]
+    pub once: bool,
+    /// Outbid competing mempool spends of puzzles we are solving
+    #[arg(long)]
+    pub auto_bump: bool,
+    /// With --auto-bump, never pay more than this many sat/vB
+    #[arg(long, value_name = "SAT/VB", requires = "auto_bump")]
+    pub max_fee_rate: Option<f64>,
+    /// Publish scheduled hints as they come due
+    #[arg(long)]
+    pub release_hints: bool,
+    /// age identity for SECRET files encrypted to a key
+    #[arg(long, value_name = "FILE", requires = "release_hints")]
+    pub identity: Option<PathBuf>,
+    /// Decrypt hints locked behind a puzzle as soon as its solve is seen
+    #[arg(long)]
+    pub unlock_hints: bool,
+}
+
+pub fn run(args: WatchArgs) -> Result<serde_json::Value> {
+    let mut sinks: Vec<Box<dyn EventSink>> = vec![Box::new(StdoutSink)];
+    for url in &args.webhook {
+        sinks.push(Box::new(WebhookSink::new(url)?));
+    }
+    for command in &args.hook {
+        sinks.push(Box::new(HookSink::new(command)));
+    }
+    if args.unlock_hints {
+        sinks.push(Box::new(HintUnlockSink::new()?));
+    }
+    if args.auto_bump {
+        sinks.push(Box::new(AutoBumpSink::new(args.max_fee_rate)?));
+    }
+
+    let cli = ElementsCli::from_config()?;
+    let db = PuzzleStore::open_default()?;
+    let mut watcher = Watcher::new(cli, db);
+    let mut releaser = args.release_hints.then(|| HintReleaser::new(args.identity));
+
+    eprintln!("👀 Puzzle watcher started (polling every {}s)", args.interval);
+
+    let mut polls = 0;
+    loop {
+        let mut polled = watcher.poll();
+        if let (Ok(events), Some(releaser)) = (&mut polled, &mut releaser) {
+            match releaser.release(watcher.cli(), watcher.db()) {
+                Ok(released) => events.extend(released),
+                Err(e) => eprintln!("⚠️  Hint release failed: {}", e),
+            }
+        }
+        match polled {
+            Ok(events) => {
+                for event in &events {
+                    for sink in sinks.iter_mut() {
+                        if let Err(e) = sink.emit(event) {
+                            eprintln!("⚠️  Failed to deliver {} event: {}", event.kind.as_str(), e);
+                        }
+                    }
+                }
+            }
+            // Keep running through node restarts
+            Err(e) => eprintln!("⚠️  Poll failed: {}", e),
+        }
+
+        polls += 1;
+        if args.once && polls >= 2 {
+            return Ok(serde_json::json!({ "polls": polls }));
+        }
+        std::thread::sleep(Duration::from_secs(args.interval));
+    }
+}
@@ -0,0 +1,77 @@
+/*
+ * CONFIDENTIAL - Blinded puzzle outputs with a shared blinding key
+ *
+ * A confidential puzzle hides its prize from casual observers. The
+ * creator generates a blinding key, funds the confidential puzzle
+ * address, and shares the blinding key with participants (in the public
+ * puzzle file, or separately). Solvers use it to unblind the puzzle
+ * output and must send the prize to a confidential address, since the
+ * blinding factors of the input have to be balanced by a blinded output.
+ */
+
+use crate::amount::Amount;
+use anyhow::{Context, Result};
+use elements::secp256k1_zkp::{PublicKey, SecretKey, SECP256K1};
+use elements::{Address, AssetId, TxOut, TxOutSecrets};
+
+/// Generate a random blinding key pair.
+pub fn generate_blinding_key() -> Result<(SecretKey, PublicKey)> {
+    let secret_key = SecretKey::from_slice(&rand::random::<[u8; 32]>())
+        .context("Failed to generate blinding key")?;
+    Ok((secret_key, PublicKey::from_secret_key(SECP256K1, &secret_key)))
+}
+
+/// Parse a hex blinding key.
+pub fn parse_blinding_key(hex_key: &str) -> Result<SecretKey> {
+    let bytes = hex::decode(hex_key.trim().trim_start_matches("0x"))
+        .context("Blinding key is not valid hex")?;
+    SecretKey::from_slice(&bytes).context("Invalid blinding key")
+}
+
+/// Fetch `txid:vout` from the node and unblind it.
+pub fn unblind_output(
+    cli: &crate::chain::ElementsCli,
+    txid: &str,
+    vout: u32,
+    blinding_key: SecretKey,
+) -> Result<(TxOut, TxOutSecrets)> {
+    let tx_hex = cli.call(&["getrawtransaction", txid])?;
+    let tx: elements::Transaction = elements::encode::deserialize(&hex::decode(tx_hex)?)
+        .context("Failed to decode funding transaction")?;
+    unblind_tx_output(&tx, vout, blinding_key)
+}
+
+/// Unblind output `vout` of an already fetched `tx`.
+pub fn unblind_tx_output(tx: &elements::Transaction, vout: u32, blinding_key: SecretKey) -> Result<(TxOut, TxOutSecrets)> {
+    let txout = tx.output.get(vout as usize)
+        .ok_or_else(|| anyhow::anyhow!("Cannot find output {} in transaction", vout))?
+        .clone();
+    let secrets = txout
+        .unblind(SECP256K1, blinding_key)
+        .map_err(|e| anyhow::anyhow!("Failed to unblind puzzle output (wrong blinding key?): {}", e))?;
+
+    Ok((txout, secrets))
+}
+
+/// A blinded output paying `value` of `asset` to a confidential
+/// `destination`, balancing the blinding factors of `spent`.
+/// All other outputs of the transaction must be explicit.
+pub fn blinded_output(value: Amount, asset: AssetId, destination: &Address, spent: &[TxOutSecrets]) -> Result<TxOut> {
+    let blinder = destination.blinding_pubkey.ok_or_else(|| {
+        anyhow::anyhow!("Destination must be a confidential address to receive a confidential prize")
+    })?;
+
+    let (txout, _, _, _) = TxOut::new_last_confidential(
+        &mut rand::thread_rng(),
+        SECP256K1,
+        value.to_sat(),
+        asset,
+        destination.script_pubkey(),
+        blinder,
+        spent,
+        &[],
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to blind destination output: {}", e))?;
+
+    Ok(txout)
+}
@@ -0,0 +1,144 @@
+/*
+ * CONTRACT - The puzzle_jackpot contract and its Taproot address
+ *
+ * A puzzle is fully determined by its target hash: compiling
+ * puzzle_jackpot.simf with TARGET_HASH gives the program, its CMR is
+ * the single Taproot leaf, and the unspendable internal key makes the
+ * leaf the only way to spend. Anyone can recompute the address from a
+ * puzzle file and check that it really is the contract it claims.
+ */
+
+use anyhow::{Context, Result};
+use elements::secp256k1_zkp::{self as secp256k1, PublicKey, XOnlyPublicKey};
+use elements::taproot::{ControlBlock, LeafVersion, TaprootBuilder, TaprootSpendInfo};
+use elements::{Address, AddressParams, Script};
+use sha2::{Digest, Sha256};
+use simplicity::Cmr;
+use simplicityhl::value::ValueConstructible;
+use simplicityhl::{Arguments, CompiledProgram, SatisfiedProgram, Value, WitnessValues};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Contracts create can build, by name; the first is the default.
+pub const CONTRACTS: [&str; 1] = ["puzzle_jackpot"];
+
+pub const PUZZLE_CONTRACT_FILE: &str = "puzzle_jackpot.simf";
+pub const PUZZLE_CONTRACT: &str = include_str!("../../SimplicityHL/examples/puzzle_jackpot.simf");
+
+/// Internal key without a known private key (NUMS point).
+pub const INTERNAL_KEY: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+/// A compiled puzzle contract and its Taproot tree.
+pub struct PuzzleContract {
+    pub target_hash: [u8; 32],
+    pub compiled: CompiledProgram,
+    pub script: Script,
+    pub leaf_version: LeafVersion,
+    pub spend_info: TaprootSpendInfo,
+}
+
+impl PuzzleContract {
+    /// Compile puzzle_jackpot.simf for `target_hash`.
+    pub fn compile(target_hash: [u8; 32]) -> Result<Self> {
+        let compiled = compile_contract(target_hash, false)?;
+
+        let script = Script::from(compiled.commit().cmr().as_ref().to_vec());
+        let leaf_version = LeafVersion::from_u8(simplicity::leaf_version().into()).expect("valid leaf version");
+        let internal_key = XOnlyPublicKey::from_str(INTERNAL_KEY)?;
+        let spend_info = TaprootBuilder::new()
+            .add_leaf_with_ver(0, script.clone(), leaf_version)
+            .expect("tap tree should be valid")
+            .finalize(secp256k1::SECP256K1, internal_key)
+            .expect("tap tree should be valid");
+
+        Ok(PuzzleContract { target_hash, compiled, script, leaf_version, spend_info })
+    }
+
+    /// The program with `secret` as its SECRET witness.
+    pub fn satisfy(&self, secret: [u8; 32]) -> Result<SatisfiedProgram> {
+        satisfy_contract(&self.compiled, secret)
+    }
+
+    /// Like `satisfy`, but compiled with debug symbols for
+    /// `execution::locate`. Its CMR differs from the real program's.
+    pub fn satisfy_with_debug_symbols(&self, secret: [u8; 32]) -> Result<SatisfiedProgram> {
+        satisfy_contract(&compile_contract(self.target_hash, true)?, secret)
+    }
+
+    pub fn cmr(&self) -> Cmr {
+        self.compiled.commit().cmr()
+    }
+
+    /// The puzzle address; confidential when `blinder` is given.
+    pub fn address(&self, blinder: Option<PublicKey>, params: &'static AddressParams) -> Address {
+        Address::p2tr(
+            secp256k1::SECP256K1,
+            self.spend_info.internal_key(),
+            self.spend_info.merkle_root(),
+            blinder,
+            params,
+        )
+    }
+
+    pub fn control_block(&self) -> ControlBlock {
+        self.spend_info
+            .control_block(&(self.script.clone(), self.leaf_version))
+            .expect("control block should exist")
+    }
+}
+
+fn compile_contract(target_hash: [u8; 32], include_debug_symbols: bool) -> Result<CompiledProgram> {
+    let mut arguments = HashMap::new();
+    arguments.insert(
+        simplicityhl::str::WitnessName::from_str_unchecked("TARGET_HASH"),
+        Value::u256(simplicityhl::num::U256::from_byte_array(target_hash)),
+    );
+    CompiledProgram::new(PUZZLE_CONTRACT, Arguments::from(arguments), include_debug_symbols)
+        .map_err(|e| anyhow::anyhow!("Failed to compile contract: {}", e))
+}
+
+fn satisfy_contract(compiled: &CompiledProgram, secret: [u8; 32]) -> Result<SatisfiedProgram> {
+    let mut witness = HashMap::new();
+    witness.insert(
+        simplicityhl::str::WitnessName::from_str_unchecked("SECRET"),
+        Value::u256(simplicityhl::num::U256::from_byte_array(secret)),
+    );
+    compiled
+        .satisfy(WitnessValues::from(witness))
+        .map_err(|e| anyhow::anyhow!("Failed to satisfy program: {}", e))
+}
+
+/// Parse a "0x..." target hash.
+pub fn parse_target_hash(hash: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(hash.trim_start_matches("0x")).context("Target hash is not hex")?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Target hash must be 32 bytes"))
+}
+
+/// A text secret as the contract sees it: right-aligned in 32 bytes.
+pub fn text_secret(secret: &str) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let raw = secret.as_bytes();
+    let len = raw.len().min(32);
+    bytes[32 - len..].copy_from_slice(&raw[..len]);
+    bytes
+}
+
+/// A secret in any format solve accepts: text, or "0x" hex (numbers and
+/// byte strings alike are right-aligned in 32 bytes).
+pub fn parse_secret(secret: &str) -> Result<[u8; 32]> {
+    let Some(hex_str) = secret.strip_prefix("0x").or_else(|| secret.strip_prefix("0X")) else {
+        return Ok(text_secret(secret));
+    };
+    let raw = hex::decode(hex_str).context("Invalid hex string")?;
+    let mut bytes = [0u8; 32];
+    let len = raw.len().min(32);
+    bytes[32 - len..].copy_from_slice(&raw[..len]);
+    Ok(bytes)
+}
+
+/// SHA256 of a 32-byte secret, the puzzle's target hash.
+pub fn hash_secret(secret: &[u8; 32]) -> [u8; 32] {
+    Sha256::digest(secret).into()
+}
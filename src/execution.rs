@@ -0,0 +1,244 @@
+/*
+ * EXECUTION - Run a Simplicity spend locally before broadcasting it
+ *
+ * The node only tells us "non-mandatory-script-verify-flag" when a
+ * Simplicity program fails. Running the redeem program on the Bit
+ * Machine ourselves, in the same environment the node would build,
+ * shows which part failed (a failed assert!/jet::verify, a pruned
+ * branch, ...) without spending anything.
+ *
+ * The environment is built from the spending transaction, the outputs
+ * it spends, the input index, the script CMR and the control block.
+ *
+ * When the contract source is known, `locate` runs a copy compiled with
+ * debug symbols under a tracker: the last tracked call before the
+ * failure is the assert!/jet that failed, and its text gives the line.
+ */
+
+use crate::chain::ElementsCli;
+use anyhow::Result;
+use elements::taproot::ControlBlock;
+use elements::{BlockHash, Transaction, TxOut};
+use simplicity::bit_machine::{ExecTracker, ExecutionError};
+use simplicity::ffi::ffi::UWORD;
+use simplicity::jet::elements::{ElementsEnv, ElementsUtxo};
+use simplicity::jet::Elements;
+use simplicity::{BitMachine, Cmr, Ihr, RedeemNode, Value};
+use simplicityhl::debug::DebugSymbols;
+use simplicityhl::SatisfiedProgram;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Genesis block hashes of the public Liquid networks.
+const LIQUID_V1_GENESIS: &str = "1466275836220db2944ca059a3a10ef6fd2ea684b0688d2c379296888a206003";
+const LIQUID_TESTNET_GENESIS: &str = "a771da8e52ee6ad581ed1e9a99825e5b3b7992225534eaa2ae23244fe26ab1c1";
+
+/// A Simplicity input of a transaction, ready to execute.
+pub struct SpendContext<'a> {
+    pub tx: &'a Transaction,
+    /// Outputs spent by every input of `tx`, in input order
+    pub spent_utxos: &'a [TxOut],
+    pub input_index: usize,
+    pub script_cmr: Cmr,
+    pub control_block: &'a ControlBlock,
+    pub annex: Option<Vec<u8>>,
+    pub genesis_hash: BlockHash,
+}
+
+/// Why a local run failed.
+#[derive(Debug)]
+pub struct ExecutionFailure {
+    pub error: String,
+    /// The failing contract call, or a plain-language reading of the error
+    pub explanation: Option<String>,
+}
+
+impl std::fmt::Display for ExecutionFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(explanation) = &self.explanation {
+            write!(f, " ({})", explanation)?;
+        }
+        Ok(())
+    }
+}
+
+/// The genesis hash the node's sighash uses: asked from the node, or
+/// known for liquidv1 and liquidtestnet.
+pub fn genesis_hash(cli: &ElementsCli) -> Result<BlockHash> {
+    if let Ok(hash) = cli.call(&["getblockhash", "0"]) {
+        return Ok(BlockHash::from_str(&hash)?);
+    }
+    match cli.chain() {
+        "liquidv1" => Ok(BlockHash::from_str(LIQUID_V1_GENESIS)?),
+        "liquidtestnet" => Ok(BlockHash::from_str(LIQUID_TESTNET_GENESIS)?),
+        chain => Err(anyhow::anyhow!("Genesis hash of {} unknown without a node", chain)),
+    }
+}
+
+/// Run `program` as the node would for `context`.
+pub fn run(program: &RedeemNode<Elements>, context: &SpendContext) -> Result<(), ExecutionFailure> {
+    let env = environment(context);
+    let mut machine = BitMachine::for_program(program).map_err(|e| ExecutionFailure {
+        error: e.to_string(),
+        explanation: Some("the program needs more memory than the Bit Machine allows".to_string()),
+    })?;
+
+    machine.exec(program, &env).map(|_| ()).map_err(|e| ExecutionFailure {
+        error: e.to_string(),
+        explanation: explain(&e).map(str::to_string),
+    })
+}
+
+/// Name the call of `source` that fails when `program`, compiled from
+/// it with debug symbols, runs for `context`: "`text` at file:line".
+pub fn locate(program: &SatisfiedProgram, file: &str, source: &str, context: &SpendContext) -> Option<String> {
+    let env = environment(context);
+    let mut machine = BitMachine::for_program(program.redeem()).ok()?;
+    let mut tracker = LastCall { symbols: program.debug_symbols(), last: None };
+    machine.exec_with_tracker(program.redeem(), &env, &mut tracker).err()?;
+
+    let call = tracker.symbols.get(&tracker.last?)?;
+    match source_line(source, call.text()) {
+        Some(line) => Some(format!("`{}` at {}:{} failed", call.text(), file, line)),
+        None => Some(format!("`{}` in {} failed", call.text(), file)),
+    }
+}
+
+fn environment(context: &SpendContext) -> ElementsEnv<Arc<Transaction>> {
+    ElementsEnv::new(
+        Arc::new(context.tx.clone()),
+        context.spent_utxos.iter().cloned().map(ElementsUtxo::from).collect(),
+        context.input_index as u32,
+        context.script_cmr,
+        context.control_block.clone(),
+        context.annex.clone(),
+        context.genesis_hash,
+    )
+}
+
+fn explain(error: &ExecutionError) -> Option<&'static str> {
+    match error {
+        ExecutionError::JetFailed(_) => Some("a jet failed: usually an assert!/jet::verify in the contract"),
+        ExecutionError::ReachedFailNode(_) => Some("the program reached a fail node"),
+        ExecutionError::ReachedPrunedBranch(_) => {
+            Some("execution took a branch that was pruned when the program was satisfied")
+        }
+        _ => None,
+    }
+}
+
+/// Remembers the last tracked SimplicityHL call the program reached.
+struct LastCall<'a> {
+    symbols: &'a DebugSymbols,
+    last: Option<Cmr>,
+}
+
+impl ExecTracker<Elements> for LastCall<'_> {
+    fn track_left(&mut self, _: Ihr) {}
+
+    fn track_right(&mut self, _: Ihr) {}
+
+    fn track_jet_call(&mut self, _: &Elements, _: &[UWORD], _: &[UWORD], _: bool) {}
+
+    fn track_dbg_call(&mut self, cmr: &Cmr, _: Value) {
+        if self.symbols.contains_key(cmr) {
+            self.last = Some(*cmr);
+        }
+    }
+
+    fn is_track_debug_enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Line of `source` where the call `text` starts. Debug symbols keep
+/// the call text with newlines and repeated spaces removed, so the
+/// source is searched the same way.
+fn source_line(source: &str, text: &str) -> Option<usize> {
+    let mut flat = String::new();
+    let mut lines = Vec::new();
+    let mut last_was_space = true;
+    for (number, line) in source.lines().enumerate() {
+        for c in line.chars() {
+            if c == ' ' && std::mem::replace(&mut last_was_space, true) {
+                continue;
+            }
+            last_was_space = c == ' ';
+            flat.push(c);
+            lines.resize(flat.len(), number + 1);
+        }
+    }
+    flat.find(text).map(|at| lines[at])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{self, PuzzleContract};
+    use elements::{confidential, AssetId, LockTime, OutPoint, Script, Sequence, TxIn, TxInWitness, TxOutWitness};
+
+    fn explicit_output(value: u64) -> TxOut {
+        TxOut {
+            value: confidential::Value::Explicit(value),
+            script_pubkey: Script::new(),
+            asset: confidential::Asset::Explicit(AssetId::default()),
+            nonce: confidential::Nonce::Null,
+            witness: TxOutWitness::empty(),
+        }
+    }
+
+    fn spend() -> Transaction {
+        Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::default(),
+                is_pegin: false,
+                script_sig: Script::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                asset_issuance: elements::AssetIssuance::null(),
+                witness: TxInWitness::empty(),
+            }],
+            output: vec![explicit_output(99_000)],
+        }
+    }
+
+    #[test]
+    fn names_the_failing_assert_for_a_wrong_secret() {
+        let puzzle = PuzzleContract::compile(contract::hash_secret(&contract::text_secret("right"))).unwrap();
+        let wrong = contract::text_secret("wrong");
+        let tx = spend();
+        let spent_utxos = [explicit_output(100_000)];
+        let control_block = puzzle.control_block();
+        let context = SpendContext {
+            tx: &tx,
+            spent_utxos: &spent_utxos,
+            input_index: 0,
+            script_cmr: puzzle.cmr(),
+            control_block: &control_block,
+            annex: None,
+            genesis_hash: BlockHash::from_str(LIQUID_TESTNET_GENESIS).unwrap(),
+        };
+
+        assert!(run(puzzle.satisfy(wrong).unwrap().redeem(), &context).is_err());
+
+        let debug = puzzle.satisfy_with_debug_symbols(wrong).unwrap();
+        let located = locate(&debug, contract::PUZZLE_CONTRACT_FILE, contract::PUZZLE_CONTRACT, &context).unwrap();
+        let line = contract::PUZZLE_CONTRACT
+            .lines()
+            .position(|line| line.contains("assert!("))
+            .unwrap()
+            + 1;
+        assert!(located.starts_with("`assert!("), "{}", located);
+        assert!(located.ends_with(&format!("at puzzle_jackpot.simf:{} failed", line)), "{}", located);
+    }
+
+    #[test]
+    fn finds_the_line_of_a_call_spread_over_lines() {
+        let source = "fn main() {\n    let x = 1;\n    assert!(jet::eq_256(\n        a,\n        b));\n}\n";
+        assert_eq!(source_line(source, "assert!(jet::eq_256( a, b))"), Some(3));
+        assert_eq!(source_line(source, "let x = 1;"), Some(2));
+        assert_eq!(source_line(source, "jet::verify(c)"), None);
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ * FEES - Fee estimation for puzzle spends
+ *
+ * A puzzle spend carries the whole Simplicity program in its witness, so
+ * a fixed fee is either wasteful or too low depending on the contract.
+ * The fee is computed from the transaction's discounted virtual size
+ * (ELIP-200, what Elements nodes use for fee rates) once the witness is
+ * attached, at a rate that is:
+ *   - given with --fee-rate <sat/vB>,
+ *   - or estimated by the node with estimatesmartfee,
+ *   - or replaced entirely by an absolute --fee <sats>.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use anyhow::Result;
+
+/// Blocks to target with estimatesmartfee.
+pub const DEFAULT_CONF_TARGET: u32 = 2;
+
+/// Liquid's minimum relay fee rate, used when the node has no estimate.
+pub const MIN_RELAY_FEE_RATE: f64 = 0.1;
+
+/// How the fee of a spend is chosen.
+#[derive(Debug, Clone, Copy)]
+pub enum FeePolicy {
+    /// Ask the node for a rate for confirmation within this many blocks
+    Estimate { conf_target: u32 },
+    /// A fixed rate in sat/vB
+    Rate(f64),
+    /// An absolute fee
+    Fixed(Amount),
+}
+
+impl Default for FeePolicy {
+    fn default() -> Self {
+        FeePolicy::Estimate { conf_target: DEFAULT_CONF_TARGET }
+    }
+}
+
+/// The chosen fee and where its rate came from.
+#[derive(Debug, Clone)]
+pub struct FeeQuote {
+    pub fee: Amount,
+    /// Rate the fee was computed from, in sat/vB
+    pub rate: f64,
+    pub source: String,
+}
+
+impl FeePolicy {
+    /// Parse --fee-rate / --fee style arguments; both given is an error.
+    pub fn from_args(fee_rate: Option<&str>, fee: Option<&str>) -> Result<Self> {
+        match (fee_rate, fee) {
+            (Some(_), Some(_)) => Err(anyhow::anyhow!("Use either --fee-rate or --fee, not both")),
+            (Some(rate), None) => {
+                let rate: f64 = rate
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid fee rate: {}", rate))?;
+                if !rate.is_finite() || rate < MIN_RELAY_FEE_RATE {
+                    return Err(anyhow::anyhow!(
+                        "Fee rate must be at least {} sat/vB",
+                        MIN_RELAY_FEE_RATE
+                    ));
+                }
+                Ok(FeePolicy::Rate(rate))
+            }
+            (None, Some(fee)) => {
+                let sats: u64 = fee.parse().map_err(|_| anyhow::anyhow!("Invalid fee in sats: {}", fee))?;
+                Ok(FeePolicy::Fixed(Amount::from_sat(sats)))
+            }
+            (None, None) => Ok(FeePolicy::default()),
+        }
+    }
+
+    /// The fee for a transaction of `vsize` discounted vbytes.
+    pub fn quote(&self, cli: &ElementsCli, vsize: usize) -> Result<FeeQuote> {
+        let (rate, source) = match *self {
+            FeePolicy::Fixed(fee) => {
+                return Ok(FeeQuote {
+                    fee,
+                    rate: effective_rate(fee, vsize),
+                    source: "--fee".to_string(),
+                });
+            }
+            FeePolicy::Rate(rate) => (rate, "--fee-rate".to_string()),
+            FeePolicy::Estimate { conf_target } => match estimate_rate(cli, conf_target)? {
+                Some(rate) => (rate, format!("estimatesmartfee {}", conf_target)),
+                None => (MIN_RELAY_FEE_RATE, "minimum relay fee (no estimate available)".to_string()),
+            },
+        };
+
+        Ok(FeeQuote {
+            fee: Amount::from_sat((rate * vsize as f64).ceil() as u64),
+            rate,
+            source,
+        })
+    }
+}
+
+/// The node's fee rate estimate in sat/vB, if it has one.
+pub fn estimate_rate(cli: &ElementsCli, conf_target: u32) -> Result<Option<f64>> {
+    let estimate = cli.call_json(&["estimatesmartfee", &conf_target.to_string()])?;
+    // feerate is in BTC/kvB
+    Ok(Amount::from_rpc(&estimate["feerate"])
+        .map(|per_kvb| (per_kvb.to_sat() as f64 / 1000.0).max(MIN_RELAY_FEE_RATE)))
+}
+
+/// Fee rate in sat/vB actually paid by `fee` for `vsize` vbytes.
+pub fn effective_rate(fee: Amount, vsize: usize) -> f64 {
+    fee.to_sat() as f64 / vsize.max(1) as f64
+}
+
+/// Discounted virtual size (ELIP-200). Confidential outputs are counted
+/// as if they were explicit: their range and surjection proofs are free
+/// and their value and nonce commitments cost as much as explicit fields.
+pub fn discount_vsize(tx: &elements::Transaction) -> usize {
+    let mut weight = tx.weight();
+
+    for output in &tx.output {
+        if output.is_fee() {
+            continue;
+        }
+        if output.value.is_confidential() {
+            // Output witness bytes count once; an explicit output would have two empty proofs
+            let witness_len = elements::encode::serialize(&output.witness).len();
+            weight = weight.saturating_sub(witness_len.saturating_sub(2));
+            // 33-byte commitment instead of 9-byte explicit value
+            weight = weight.saturating_sub((33 - 9) * 4);
+        }
+        if output.nonce.is_confidential() {
+            // 33-byte nonce instead of the 1-byte null nonce
+            weight = weight.saturating_sub((33 - 1) * 4);
+        }
+    }
+
+    weight.div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elements::confidential::{self, AssetBlindingFactor, ValueBlindingFactor};
+    use elements::secp256k1_zkp::{PublicKey, SecretKey, SECP256K1};
+    use elements::{Address, AddressParams, AssetId, LockTime, OutPoint, Transaction, TxIn, TxOut, TxOutSecrets};
+
+    fn transaction(output: TxOut) -> Transaction {
+        // Puzzle spends always carry a witness
+        let mut input = TxIn { previous_output: OutPoint::default(), ..Default::default() };
+        input.witness.script_witness = vec![vec![1; 64]];
+        Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![input],
+            output: vec![output, TxOut::new_fee(500, AssetId::default())],
+        }
+    }
+
+    #[test]
+    fn discounts_confidential_outputs_to_their_explicit_size() {
+        let asset = AssetId::default();
+        let key = |byte| PublicKey::from_secret_key(SECP256K1, &SecretKey::from_slice(&[byte; 32]).unwrap());
+        let destination = Address::p2wpkh(
+            &elements::bitcoin::PublicKey::new(key(1)),
+            Some(key(2)),
+            &AddressParams::LIQUID_TESTNET,
+        );
+        let spent = TxOutSecrets::new(asset, AssetBlindingFactor::zero(), 100_000, ValueBlindingFactor::zero());
+        let blinded = crate::confidential::blinded_output(Amount::from_sat(99_500), asset, &destination, &[spent]).unwrap();
+        assert!(blinded.value.is_confidential() && blinded.nonce.is_confidential());
+
+        let explicit = TxOut {
+            value: confidential::Value::Explicit(99_500),
+            asset: confidential::Asset::Explicit(asset),
+            nonce: confidential::Nonce::Null,
+            script_pubkey: destination.script_pubkey(),
+            witness: Default::default(),
+        };
+
+        let explicit_tx = transaction(explicit);
+        assert_eq!(discount_vsize(&explicit_tx), explicit_tx.weight().div_ceil(4));
+        let blinded_tx = transaction(blinded);
+        assert!(!blinded_tx.output[0].witness.is_empty());
+        assert_eq!(discount_vsize(&blinded_tx), discount_vsize(&explicit_tx));
+    }
+
+    #[test]
+    fn parses_fee_arguments() {
+        assert!(matches!(FeePolicy::from_args(None, None).unwrap(), FeePolicy::Estimate { conf_target: DEFAULT_CONF_TARGET }));
+        assert!(matches!(FeePolicy::from_args(Some("0.25"), None).unwrap(), FeePolicy::Rate(rate) if rate == 0.25));
+        assert!(matches!(FeePolicy::from_args(None, Some("300")).unwrap(), FeePolicy::Fixed(fee) if fee.to_sat() == 300));
+
+        let both = FeePolicy::from_args(Some("1"), Some("300")).unwrap_err();
+        assert_eq!(both.to_string(), "Use either --fee-rate or --fee, not both");
+        for rate in ["0.05", "NaN", "inf", "fast"] {
+            assert!(FeePolicy::from_args(Some(rate), None).is_err(), "{}", rate);
+        }
+        for fee in ["0.0001", "-1", "300 sat"] {
+            assert!(FeePolicy::from_args(None, Some(fee)).is_err(), "{}", fee);
+        }
+    }
+
+    #[test]
+    fn rounds_fixed_rates_up_to_whole_satoshis() {
+        // A fixed rate never asks the node
+        let cli = ElementsCli::from_config().unwrap();
+        let quote = FeePolicy::Rate(0.1).quote(&cli, 1_234).unwrap();
+        assert_eq!(quote.fee.to_sat(), 124);
+        assert_eq!(quote.source, "--fee-rate");
+    }
+}
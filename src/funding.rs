@@ -0,0 +1,116 @@
+/*
+ * FUNDING - Build puzzle funding transactions and locate their outputs
+ *
+ * The contract hashes jet::current_value(), which only works when the
+ * puzzle output carries an explicit value and asset. Instead of a wallet
+ * sendtoaddress, the funding transaction is built step by step:
+ *   createrawtransaction -> fundrawtransaction -> blindrawtransaction
+ *   -> signrawtransactionwithwallet -> sendrawtransaction
+ * The puzzle address has no blinding key, so its output stays explicit
+ * while the wallet's change output is blinded as usual. A confidential
+ * puzzle address (see confidential.rs) gets a blinded output instead.
+ *
+ * The wallet still puts the puzzle output at a random position among
+ * the change outputs, so the vout is read back from the transaction by
+ * matching the puzzle's script pubkey.
+ *
+ * A campaign funds many puzzles in one transaction, one output each.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use anyhow::Result;
+use serde::Serialize;
+
+/// The output of a funding transaction that pays a puzzle.
+#[derive(Debug, Clone, Serialize)]
+pub struct FundingOutput {
+    pub vout: u32,
+    /// Explicit value, when the output is not blinded
+    pub value: Option<Amount>,
+    pub value_commitment: Option<String>,
+    /// Explicit asset id, when the output is not blinded
+    pub asset: Option<String>,
+    pub asset_commitment: Option<String>,
+}
+
+/// Fund `address` with `amount` L-BTC and broadcast. The output is
+/// explicit unless `address` is confidential. Returns the funding txid.
+pub fn fund_puzzle(cli: &ElementsCli, address: &str, amount: Amount) -> Result<String> {
+    fund_puzzles(cli, &[(address.to_string(), amount)])
+}
+
+/// Fund several puzzle addresses in one transaction, one output each.
+/// Returns the funding txid.
+pub fn fund_puzzles(cli: &ElementsCli, payments: &[(String, Amount)]) -> Result<String> {
+    let outputs: Vec<serde_json::Value> = payments
+        .iter()
+        .map(|(address, amount)| serde_json::json!({ address.as_str(): amount.to_rpc() }))
+        .collect();
+    let outputs = serde_json::Value::Array(outputs);
+
+    let raw = cli.call(&["createrawtransaction", "[]", &outputs.to_string()])?;
+    let funded = cli.call_wallet_json(&["fundrawtransaction", &raw])?;
+    let funded_hex = funded["hex"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("fundrawtransaction returned no hex"))?;
+
+    // Blinds the change; the puzzle output is only blinded if its address has a blinding key
+    let blinded = cli.call_wallet(&["blindrawtransaction", funded_hex])?;
+
+    let signed = cli.call_wallet_json(&["signrawtransactionwithwallet", &blinded])?;
+    if signed["complete"].as_bool() != Some(true) {
+        return Err(anyhow::anyhow!("Wallet could not sign the funding transaction: {}", signed["errors"]));
+    }
+    let signed_hex = signed["hex"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("signrawtransactionwithwallet returned no hex"))?;
+
+    cli.call(&["sendrawtransaction", signed_hex])
+}
+
+/// Find the single output of `txid` paying `script_pubkey`.
+/// Fails if none or several outputs match.
+pub fn locate_output(cli: &ElementsCli, txid: &str, script_pubkey: &elements::Script) -> Result<FundingOutput> {
+    let tx = cli.get_raw_transaction(txid)?;
+    let script_hex = hex::encode(script_pubkey.as_bytes());
+
+    let matches: Vec<&serde_json::Value> = tx["vout"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|output| output["scriptPubKey"]["hex"].as_str() == Some(script_hex.as_str()))
+        .collect();
+
+    match matches.as_slice() {
+        [output] => Ok(FundingOutput {
+            vout: output["n"].as_u64()
+                .ok_or_else(|| anyhow::anyhow!("Output without index in {}", txid))? as u32,
+            value: Amount::from_rpc(&output["value"]),
+            value_commitment: output["valuecommitment"].as_str().map(str::to_string),
+            asset: output["asset"].as_str().map(str::to_string),
+            asset_commitment: output["assetcommitment"].as_str().map(str::to_string),
+        }),
+        [] => Err(anyhow::anyhow!(
+            "❌ No output of {} pays the puzzle script {}",
+            txid,
+            script_hex
+        )),
+        several => Err(anyhow::anyhow!(
+            "❌ {} outputs of {} pay the puzzle script, expected exactly one",
+            several.len(),
+            txid
+        )),
+    }
+}
+
+impl FundingOutput {
+    /// Fail unless value and asset are explicit, so the contract can read them.
+    pub fn require_explicit(&self) -> Result<(Amount, &str)> {
+        match (self.value, self.asset.as_deref()) {
+            (Some(value), Some(asset)) => Ok((value, asset)),
+            _ => Err(anyhow::anyhow!(
+                "❌ Puzzle output {} is blinded; jet::current_value() needs an explicit value and asset",
+                self.vout
+            )),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+/*
+ * HINTS - Hints released on a schedule
+ *
+ * Besides its fixed hint, a puzzle can have a hint schedule: hints that
+ * become public at a block height or at a time. The schedule is kept in
+ * the encrypted SECRET file as "hint_schedule":
+ *
+ *   [ { "at": 1200, "hint": "It is a name" },
+ *     { "at": "2026-11-01T12:00:00Z", "hint": "Starts with S" } ]
+ *
+ * The public file only carries the hints already released, in "hints",
+ * and how many are still to come, in "pending_hints". Releasing decrypts
+ * the SECRET file, so it is done by the creator: with `puzzle-hunt
+ * release-hints`, or by puzzle-watcher --release-hints, which needs
+ * PUZZLE_SECRET_PASSPHRASE or --identity to run unattended.
+ */
+
+use crate::chain::ElementsCli;
+use crate::secret_file;
+use crate::store::{self, PuzzleRecord, PuzzleStore};
+use crate::watcher::{Event, EventKind};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// When a hint becomes public: a block height or an RFC 3339 time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Release {
+    Height(u64),
+    Time(String),
+}
+
+impl Release {
+    /// Whether the hint is due at chain `height` and time `now`.
+    pub fn is_due(&self, height: u64, now: DateTime<Utc>) -> Result<bool> {
+        match self {
+            Release::Height(release) => Ok(height >= *release),
+            Release::Time(time) => Ok(now >= parse_time(time)?),
+        }
+    }
+
+    fn check(&self) -> Result<()> {
+        if let Release::Time(time) = self {
+            parse_time(time)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Release {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Release::Height(height) => write!(f, "block {}", height),
+            Release::Time(time) => write!(f, "{}", time),
+        }
+    }
+}
+
+fn parse_time(time: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|t| t.with_timezone(&Utc))
+        .with_context(|| format!("Invalid release time {} (expected RFC 3339, e.g. 2026-11-01T12:00:00Z)", time))
+}
+
+/// One entry of a hint schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledHint {
+    pub at: Release,
+    pub hint: String,
+}
+
+impl ScheduledHint {
+    pub fn check(&self) -> Result<()> {
+        self.at.check()
+    }
+}
+
+/// `<height>=<hint>` or `<RFC 3339 time>=<hint>`, as given to create --hint-at.
+impl FromStr for ScheduledHint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (at, hint) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected <height>=<hint> or <time>=<hint>, got {}", s))?;
+        let at = match at.trim().parse() {
+            Ok(height) => Release::Height(height),
+            Err(_) => Release::Time(at.trim().to_string()),
+        };
+        let scheduled = ScheduledHint { at, hint: hint.to_string() };
+        scheduled.check()?;
+        Ok(scheduled)
+    }
+}
+
+/// A hint published in the public file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleasedHint {
+    /// Position in the schedule
+    pub index: usize,
+    pub at: Release,
+    pub hint: String,
+    pub released_at: String,
+}
+
+/// Move the hints of `schedule` that are due into the public file at
+/// `public_path`. Returns the newly released hints.
+pub fn release_due(
+    public_path: &Path,
+    schedule: &[ScheduledHint],
+    height: u64,
+    now: DateTime<Utc>,
+) -> Result<Vec<ReleasedHint>> {
+    let mut public: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(public_path).with_context(|| format!("Failed to read {}", public_path.display()))?,
+    )
+    .context("Failed to parse puzzle JSON")?;
+
+    let mut released: Vec<ReleasedHint> = match public.get("hints") {
+        Some(hints) if !hints.is_null() => serde_json::from_value(hints.clone()).context("Invalid hints in puzzle file")?,
+        _ => vec![],
+    };
+
+    let mut new = vec![];
+    for (index, scheduled) in schedule.iter().enumerate() {
+        if released.iter().any(|r| r.index == index) || !scheduled.at.is_due(height, now)? {
+            continue;
+        }
+        new.push(ReleasedHint {
+            index,
+            at: scheduled.at.clone(),
+            hint: scheduled.hint.clone(),
+            released_at: now.to_rfc3339(),
+        });
+    }
+    if new.is_empty() {
+        return Ok(new);
+    }
+
+    released.extend(new.iter().cloned());
+    released.sort_by_key(|r| r.index);
+    public["pending_hints"] = serde_json::json!(schedule.len().saturating_sub(released.len()));
+    public["hints"] = serde_json::to_value(&released)?;
+    std::fs::write(public_path, serde_json::to_string_pretty(&public)?)
+        .with_context(|| format!("Failed to write {}", public_path.display()))?;
+
+    Ok(new)
+}
+
+/// Whether a public file still has hints to release.
+pub fn has_pending(public_path: &Path) -> bool {
+    std::fs::read_to_string(public_path)
+        .ok()
+        .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+        .and_then(|public| public["pending_hints"].as_u64())
+        .is_some_and(|pending| pending > 0)
+}
+
+/// Releases due hints of every active puzzle in the database. Decrypted
+/// schedules are kept, so each SECRET file is only decrypted once.
+pub struct HintReleaser {
+    identity: Option<PathBuf>,
+    schedules: HashMap<String, Vec<ScheduledHint>>,
+}
+
+impl HintReleaser {
+    pub fn new(identity: Option<PathBuf>) -> Self {
+        HintReleaser { identity, schedules: HashMap::new() }
+    }
+
+    /// Release what is due now, as hint_released events.
+    pub fn release(&mut self, cli: &ElementsCli, db: &PuzzleStore) -> Result<Vec<Event>> {
+        let height: u64 = cli.call(&["getblockcount"])?.trim().parse().context("Invalid block count")?;
+        let now = Utc::now();
+
+        let mut events = vec![];
+        for puzzle in db.list_puzzles()? {
+            if puzzle.status != store::STATUS_ACTIVE {
+                continue;
+            }
+            let (Some(public_file), Some(secret_file)) = (&puzzle.public_file, &puzzle.secret_file) else {
+                continue;
+            };
+            if !has_pending(Path::new(public_file)) {
+                continue;
+            }
+            // One unreadable SECRET file must not hold back the other puzzles
+            let released = self
+                .schedule(&puzzle.target_hash, Path::new(secret_file))
+                .and_then(|schedule| release_due(Path::new(public_file), &schedule, height, now));
+            match released {
+                Ok(hints) => {
+                    for hint in hints {
+                        events.push(released_event(db, &puzzle, &hint, height)?);
+                    }
+                }
+                Err(e) => eprintln!("⚠️  Could not release hints of {}: {}", puzzle.target_hash, e),
+            }
+        }
+        Ok(events)
+    }
+
+    fn schedule(&mut self, target_hash: &str, secret_file: &Path) -> Result<Vec<ScheduledHint>> {
+        if let Some(schedule) = self.schedules.get(target_hash) {
+            return Ok(schedule.clone());
+        }
+        let schedule = read_schedule(secret_file, self.identity.as_deref())?;
+        self.schedules.insert(target_hash.to_string(), schedule.clone());
+        Ok(schedule)
+    }
+}
+
+/// The hint schedule in a SECRET file (empty if it has none).
+pub fn read_schedule(secret_file: &Path, identity: Option<&Path>) -> Result<Vec<ScheduledHint>> {
+    let private_info = secret_file::read_secret_file(secret_file, identity)?;
+    match private_info.get("hint_schedule") {
+        Some(schedule) if !schedule.is_null() => {
+            serde_json::from_value(schedule.clone()).context("Invalid hint_schedule in SECRET file")
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+/// A hint_released event, recorded in the database.
+pub fn released_event(db: &PuzzleStore, puzzle: &PuzzleRecord, hint: &ReleasedHint, height: u64) -> Result<Event> {
+    let outpoint = db.funding_outpoints(puzzle.id)?.into_iter().next();
+    let event = Event {
+        kind: EventKind::HintReleased,
+        target_hash: puzzle.target_hash.clone(),
+        address: puzzle.address.clone(),
+        txid: outpoint.as_ref().map(|o| o.txid.clone()).unwrap_or_default(),
+        vout: outpoint.as_ref().map(|o| o.vout).unwrap_or_default(),
+        amount_sats: None,
+        block_height: Some(height),
+        solve: None,
+        detail: Some(hint.hint.clone()),
+        time: chrono::Local::now().to_rfc3339(),
+    };
+    db.record_event(
+        puzzle.id,
+        event.kind.as_str(),
+        Some(&event.txid),
+        Some(event.vout),
+        event.block_height,
+        &serde_json::to_value(&event)?,
+    )?;
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public_file(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("puzzle-hunt-hints-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("puzzle_0x8c4f.json");
+        std::fs::write(&path, serde_json::json!({ "hash": "0x8c4f", "pending_hints": 3 }).to_string()).unwrap();
+        path
+    }
+
+    fn schedule() -> Vec<ScheduledHint> {
+        ["1200=It is a name", "2026-11-01T12:00:00Z=Starts with S", "1500=Ends with y"]
+            .iter()
+            .map(|entry| entry.parse().unwrap())
+            .collect()
+    }
+
+    fn time(time: &str) -> DateTime<Utc> {
+        parse_time(time).unwrap()
+    }
+
+    #[test]
+    fn parses_heights_and_times() {
+        let schedule = schedule();
+        assert_eq!(schedule[0].at, Release::Height(1200));
+        assert_eq!(schedule[1].at, Release::Time("2026-11-01T12:00:00Z".to_string()));
+        assert_eq!(schedule[1].hint, "Starts with S");
+        assert!("tomorrow=Soon".parse::<ScheduledHint>().is_err());
+        assert!("1200".parse::<ScheduledHint>().is_err());
+    }
+
+    #[test]
+    fn is_due_at_its_height_or_time() {
+        let before = time("2026-11-01T11:59:59Z");
+        let after = time("2026-11-01T12:00:00Z");
+        assert!(!Release::Height(1200).is_due(1199, after).unwrap());
+        assert!(Release::Height(1200).is_due(1200, before).unwrap());
+        let release = Release::Time("2026-11-01T12:00:00Z".to_string());
+        assert!(!release.is_due(u64::MAX, before).unwrap());
+        assert!(release.is_due(0, after).unwrap());
+    }
+
+    #[test]
+    fn releases_each_due_hint_once() {
+        let path = public_file("release");
+        let schedule = schedule();
+
+        let first = release_due(&path, &schedule, 1200, time("2026-10-01T00:00:00Z")).unwrap();
+        assert_eq!(first.iter().map(|r| r.index).collect::<Vec<_>>(), vec![0]);
+
+        let again = release_due(&path, &schedule, 1300, time("2026-10-02T00:00:00Z")).unwrap();
+        assert!(again.is_empty());
+
+        let rest = release_due(&path, &schedule, 1500, time("2026-11-02T00:00:00Z")).unwrap();
+        assert_eq!(rest.iter().map(|r| r.index).collect::<Vec<_>>(), vec![1, 2]);
+
+        let public: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(public["pending_hints"], 0);
+        let hints: Vec<ReleasedHint> = serde_json::from_value(public["hints"].clone()).unwrap();
+        assert_eq!(hints.iter().map(|h| h.hint.as_str()).collect::<Vec<_>>(), ["It is a name", "Starts with S", "Ends with y"]);
+        assert_eq!(hints[0].released_at, "2026-10-01T00:00:00+00:00");
+    }
+}
@@ -0,0 +1,268 @@
+/*
+ * LEADERBOARD - Winners ranked from the recorded on-chain solves
+ *
+ * Every verified solve in the puzzle database (see solves.rs) credits
+ * the address its prize went to. Participants are those addresses, named
+ * by their registered nickname if they have one (registration.rs), and
+ * ranked by number of solves, then total prize, then who solved first.
+ *
+ * Time to solve is from the puzzle's creation to the block holding its
+ * solve; solves still in the mempool have none yet. Spends whose secret
+ * does not match the target hash are left out.
+ *
+ * The prize of a solve is what its transaction took from the puzzle:
+ * the funding outputs recorded as spent by it (record_solve marks each
+ * one as its spend is found). Puzzles back to active, e.g. after their
+ * solve was reorged out or when part of the pot is still unspent, are
+ * not counted yet.
+ */
+
+use crate::amount::Amount;
+use crate::store::{self, PuzzleRecord, PuzzleStore};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// One solved puzzle, as credited to its winner.
+#[derive(Debug, Clone, Serialize)]
+pub struct SolvedPuzzle {
+    pub target_hash: String,
+    pub txid: String,
+    pub prize: Amount,
+    pub secret: Option<String>,
+    pub block_height: Option<u64>,
+    pub created_at: Option<String>,
+    /// RFC 3339 time of the solve's block
+    pub solved_at: Option<String>,
+    pub time_to_solve_secs: Option<i64>,
+}
+
+/// A winning address and what it won.
+#[derive(Debug, Clone, Serialize)]
+pub struct Participant {
+    pub rank: usize,
+    pub address: String,
+    /// Registered nickname (see registration.rs)
+    pub nickname: Option<String>,
+    pub solves: usize,
+    pub total_prize: Amount,
+    pub fastest_solve_secs: Option<i64>,
+    pub puzzles: Vec<SolvedPuzzle>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Leaderboard {
+    pub participants: Vec<Participant>,
+    pub solved_puzzles: usize,
+    pub total_prize: Amount,
+    /// Solves left out: unverified secret, unknown destination or no
+    /// recorded spent output
+    pub skipped: usize,
+}
+
+/// Build the leaderboard from the solves in `db`, ignoring solves paid
+/// to an address in `exclude` (e.g. the organizer's reclaims).
+pub fn build(db: &PuzzleStore, exclude: &[String]) -> Result<Leaderboard> {
+    let nicknames: HashMap<String, String> =
+        db.participants()?.into_iter().map(|p| (p.address, p.nickname)).collect();
+    let mut by_address: HashMap<String, Vec<SolvedPuzzle>> = HashMap::new();
+    let mut skipped = 0;
+
+    for puzzle in db.list_puzzles()? {
+        if puzzle.status == store::STATUS_ACTIVE {
+            continue;
+        }
+        let Some(solve) = db.solve_for(puzzle.id)? else {
+            continue;
+        };
+        let prize = prize(db, &puzzle, &solve.txid)?;
+        let destination = match &solve.destination {
+            Some(destination) if solve.verified && prize > Amount::ZERO => destination.clone(),
+            _ => {
+                skipped += 1;
+                continue;
+            }
+        };
+        if exclude.contains(&destination) {
+            continue;
+        }
+
+        let solved_at = solve.block_time.and_then(|t| DateTime::from_timestamp(t, 0));
+        let created_at = puzzle.created_at.as_deref().and_then(|c| DateTime::parse_from_rfc3339(c).ok());
+        let time_to_solve_secs = match (created_at, solved_at) {
+            (Some(created), Some(solved)) => Some((solved - created.with_timezone(&Utc)).num_seconds().max(0)),
+            _ => None,
+        };
+
+        by_address.entry(destination).or_default().push(SolvedPuzzle {
+            target_hash: puzzle.target_hash.clone(),
+            prize,
+            txid: solve.txid.clone(),
+            secret: solve.secret_text.clone().or_else(|| solve.secret_hex.as_ref().map(|h| format!("0x{}", h))),
+            block_height: solve.block_height,
+            created_at: puzzle.created_at.clone(),
+            solved_at: solved_at.map(|t| t.to_rfc3339()),
+            time_to_solve_secs,
+        });
+    }
+
+    let mut participants: Vec<Participant> = by_address
+        .into_iter()
+        .map(|(address, mut puzzles)| {
+            puzzles.sort_by_key(|p| p.block_height.unwrap_or(u64::MAX));
+            Participant {
+                rank: 0,
+                nickname: nicknames.get(&address).cloned(),
+                address,
+                solves: puzzles.len(),
+                total_prize: puzzles.iter().map(|p| p.prize).sum(),
+                fastest_solve_secs: puzzles.iter().filter_map(|p| p.time_to_solve_secs).min(),
+                puzzles,
+            }
+        })
+        .collect();
+
+    // Unconfirmed solves rank after confirmed ones
+    let first_solve = |p: &Participant| p.puzzles.first().and_then(|s| s.block_height).unwrap_or(u64::MAX);
+    participants.sort_by(|a, b| {
+        b.solves
+            .cmp(&a.solves)
+            .then(b.total_prize.cmp(&a.total_prize))
+            .then(first_solve(a).cmp(&first_solve(b)))
+    });
+    for (i, participant) in participants.iter_mut().enumerate() {
+        participant.rank = i + 1;
+    }
+
+    Ok(Leaderboard {
+        solved_puzzles: participants.iter().map(|p| p.solves).sum(),
+        total_prize: participants.iter().map(|p| p.total_prize).sum(),
+        participants,
+        skipped,
+    })
+}
+
+/// What the solve took: the puzzle's funding outputs spent by `txid`.
+/// Zero when none is recorded.
+fn prize(db: &PuzzleStore, puzzle: &PuzzleRecord, txid: &str) -> Result<Amount> {
+    Ok(db
+        .funding_outpoints(puzzle.id)?
+        .into_iter()
+        .filter(|o| o.spent_by.as_deref() == Some(txid))
+        .filter_map(|o| o.amount_sats)
+        .sum())
+}
+
+/// A duration as "2d 3h", "3h 12m", "12m 5s" or "5s".
+pub fn format_duration(secs: i64) -> String {
+    let (days, hours, minutes, seconds) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60, secs % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, _) => format!("{}m {}s", minutes, seconds),
+        (0, _, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solves::SolveInfo;
+    use crate::store::NewPuzzle;
+    use std::path::Path;
+
+    /// 2026-01-01T00:00:00Z
+    const CREATED: i64 = 1_767_225_600;
+
+    fn add_puzzle(db: &PuzzleStore, target_hash: &str, pot: &[u64]) -> i64 {
+        let (id, _) = db
+            .insert_puzzle(&NewPuzzle {
+                target_hash: target_hash.to_string(),
+                address: format!("tex1{}", target_hash),
+                network: "liquidtestnet".to_string(),
+                contract: "puzzle_jackpot".to_string(),
+                amount_sats: pot.first().map(|sats| Amount::from_sat(*sats)),
+                hint: None,
+                created_at: Some("2026-01-01T00:00:00Z".to_string()),
+                public_file: None,
+                secret_file: None,
+            })
+            .unwrap();
+        for (vout, sats) in pot.iter().enumerate() {
+            db.add_funding_outpoint(id, &format!("fund-{}", target_hash), vout as u32, Some(Amount::from_sat(*sats)), None)
+                .unwrap();
+        }
+        id
+    }
+
+    fn solve(db: &PuzzleStore, puzzle_id: i64, vout: u32, txid: &str, winner: &str, height: u64, verified: bool) {
+        let target_hash = db.list_puzzles().unwrap().into_iter().find(|p| p.id == puzzle_id).unwrap().target_hash;
+        let solve = SolveInfo {
+            txid: txid.to_string(),
+            secret_hex: None,
+            secret_text: None,
+            verified,
+            destination: Some(winner.to_string()),
+            block_height: Some(height),
+            block_time: Some(CREATED + height as i64 * 60),
+            winner: None,
+        };
+        db.record_solve(puzzle_id, &format!("fund-{}", target_hash), vout, &solve).unwrap();
+    }
+
+    #[test]
+    fn ranks_by_solves_then_prize_then_first_solve() {
+        let db = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        let a = add_puzzle(&db, "aa", &[1_000]);
+        let b = add_puzzle(&db, "bb", &[2_000]);
+        let c = add_puzzle(&db, "cc", &[5_000]);
+        let d = add_puzzle(&db, "dd", &[5_000]);
+        // Unsolved puzzles are not counted
+        add_puzzle(&db, "ee", &[9_000]);
+        solve(&db, a, 0, "spend-a", "tex1alice", 20, true);
+        solve(&db, b, 0, "spend-b", "tex1alice", 10, true);
+        solve(&db, c, 0, "spend-c", "tex1bob", 30, true);
+        solve(&db, d, 0, "spend-d", "tex1carol", 15, true);
+        db.register_participant("tex1carol", "carol", "sig", 0).unwrap();
+
+        let board = build(&db, &[]).unwrap();
+        let ranking: Vec<_> = board.participants.iter().map(|p| (p.rank, p.address.as_str(), p.solves)).collect();
+        assert_eq!(ranking, [(1, "tex1alice", 2), (2, "tex1carol", 1), (3, "tex1bob", 1)]);
+
+        let alice = &board.participants[0];
+        assert_eq!(alice.total_prize, Amount::from_sat(3_000));
+        assert_eq!(alice.puzzles.iter().map(|p| p.txid.as_str()).collect::<Vec<_>>(), ["spend-b", "spend-a"]);
+        assert_eq!(alice.fastest_solve_secs, Some(600));
+        assert_eq!(board.participants[1].nickname.as_deref(), Some("carol"));
+        assert_eq!((board.solved_puzzles, board.total_prize, board.skipped), (4, Amount::from_sat(13_000), 0));
+
+        let without_alice = build(&db, &["tex1alice".to_string()]).unwrap();
+        assert_eq!(without_alice.participants[0].address, "tex1carol");
+        assert_eq!(without_alice.solved_puzzles, 2);
+    }
+
+    #[test]
+    fn credits_each_solve_with_the_outputs_it_spent() {
+        let db = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        // The pot was topped up, and the two outputs spent by different solves
+        let pot = add_puzzle(&db, "aa", &[1_000, 4_000]);
+        solve(&db, pot, 0, "first-spend", "tex1alice", 10, true);
+        solve(&db, pot, 1, "second-spend", "tex1bob", 11, true);
+        let unverified = add_puzzle(&db, "bb", &[2_000]);
+        solve(&db, unverified, 0, "spend-b", "tex1carol", 12, false);
+
+        let board = build(&db, &[]).unwrap();
+        let prizes: Vec<_> = board.participants.iter().map(|p| (p.address.as_str(), p.total_prize)).collect();
+        assert_eq!(prizes, [("tex1bob", Amount::from_sat(4_000))]);
+        assert_eq!(board.skipped, 1);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(5), "5s");
+        assert_eq!(format_duration(725), "12m 5s");
+        assert_eq!(format_duration(11_520), "3h 12m");
+        assert_eq!(format_duration(183_600), "2d 3h");
+    }
+}
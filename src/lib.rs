@@ -0,0 +1,30 @@
+/*
+ * PUZZLE HUNT - Shared library for the puzzle hunt binaries
+ *
+ * Code used by more than one binary lives here so that
+ * create-puzzle, solve-puzzle and friends agree on file formats.
+ */
+
+pub mod amount;
+pub mod budget;
+pub mod campaign;
+pub mod chain;
+pub mod commands;
+pub mod confidential;
+pub mod contract;
+pub mod execution;
+pub mod fees;
+pub mod funding;
+pub mod hints;
+pub mod leaderboard;
+pub mod locked_hints;
+pub mod output;
+pub mod pset;
+pub mod rbf;
+pub mod registration;
+pub mod secret_file;
+pub mod server;
+pub mod solves;
+pub mod store;
+pub mod wallet;
+pub mod watcher;
@@ -0,0 +1,235 @@
+/*
+ * LOCKED HINTS - Hints that unlock without the organizer
+ *
+ * A locked hint is published from the start, encrypted (age, with a
+ * passphrase) under a key derived from another puzzle's secret. The
+ * solve of that puzzle reveals the secret on-chain, so it unlocks the
+ * hint for everyone: solving puzzle N opens puzzle N+1's hint. No key
+ * is held back by the organizer, which is why there are no hints locked
+ * until a block height: a key for those would have to be kept and
+ * released by someone.
+ *
+ * In the public file:
+ *
+ *   "locked_hints": [
+ *     { "unlock": { "solved": "0x2cf24dba..." }, "ciphertext": "-----BEGIN AGE...", "hint": "..." } ]
+ *
+ * "hint" appears once someone ran unlock-hints (or puzzle-watcher
+ * --unlock-hints saw the solve). The ciphertext is fixed at creation, so
+ * the organizer cannot change a hint afterwards.
+ *
+ * Campaign files declare them per puzzle (after is a 1-based position
+ * in the campaign):
+ *
+ *   locked_hints:
+ *     - after: 1
+ *       hint: The next secret is a planet
+ */
+
+use crate::contract;
+use crate::store::PuzzleStore;
+use crate::watcher::{Event, EventKind, EventSink};
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::secrecy::Secret;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Domain separation, so the key is not the public target hash.
+const KEY_TAG: &[u8] = b"puzzle-hunt/hint-key";
+
+/// What makes the key of a locked hint public.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unlock {
+    /// The solve of the puzzle with this target hash
+    Solved(String),
+}
+
+/// A locked hint as it appears in the public file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedHint {
+    pub unlock: Unlock,
+    pub ciphertext: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+/// A locked hint in a campaign file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockedHintSpec {
+    /// 1-based position of the puzzle whose solve unlocks the hint
+    pub after: usize,
+    pub hint: String,
+}
+
+/// The key unlocked by a puzzle's (32-byte) secret.
+pub fn solve_key(secret: &[u8; 32]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(KEY_TAG);
+    hasher.update(secret);
+    hex::encode(hasher.finalize())
+}
+
+pub fn encrypt(hint: &str, key: &str) -> Result<String> {
+    let encryptor = age::Encryptor::with_user_passphrase(Secret::new(key.to_string()));
+    let mut ciphertext = vec![];
+    let armored = ArmoredWriter::wrap_output(&mut ciphertext, Format::AsciiArmor)?;
+    let mut writer = encryptor
+        .wrap_output(armored)
+        .map_err(|e| anyhow::anyhow!("Failed to encrypt hint: {}", e))?;
+    writer.write_all(hint.as_bytes())?;
+    writer.finish()?.finish()?;
+    Ok(String::from_utf8(ciphertext)?)
+}
+
+pub fn decrypt(ciphertext: &str, key: &str) -> Result<String> {
+    let decryptor = match age::Decryptor::new(ArmoredReader::new(ciphertext.as_bytes()))
+        .map_err(|e| anyhow::anyhow!("Invalid locked hint: {}", e))?
+    {
+        age::Decryptor::Passphrase(decryptor) => decryptor,
+        age::Decryptor::Recipients(_) => return Err(anyhow::anyhow!("Locked hint is not passphrase-encrypted")),
+    };
+    let mut plaintext = vec![];
+    decryptor
+        .decrypt(&Secret::new(key.to_string()), None)
+        .map_err(|e| anyhow::anyhow!("Failed to decrypt hint: {}", e))?
+        .read_to_end(&mut plaintext)?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+/// Lock the hints of one campaign puzzle. `secrets` are the text secrets
+/// of the campaign and `target_hashes` their hashes, in campaign order.
+pub fn lock(specs: &[LockedHintSpec], secrets: &[String], target_hashes: &[String]) -> Result<Vec<LockedHint>> {
+    let mut locked = vec![];
+    for spec in specs {
+        let index = spec.after.checked_sub(1).filter(|i| *i < secrets.len()).ok_or_else(|| {
+            anyhow::anyhow!("Locked hint after puzzle {}: the campaign has {} puzzles", spec.after, secrets.len())
+        })?;
+        let key = solve_key(&contract::text_secret(&secrets[index]));
+        locked.push(LockedHint {
+            unlock: Unlock::Solved(target_hashes[index].clone()),
+            ciphertext: encrypt(&spec.hint, &key)?,
+            hint: None,
+        });
+    }
+    Ok(locked)
+}
+
+/// Decrypt every locked hint of the public file whose puzzle's secret is
+/// known, from `secrets` (secret, target hash) or from a verified solve in
+/// `db`.
+/// Writes the hints back and returns the newly unlocked ones.
+pub fn unlock(public_path: &Path, secrets: &[([u8; 32], String)], db: Option<&PuzzleStore>) -> Result<Vec<LockedHint>> {
+    let mut public = read_public(public_path)?;
+    let mut locked = locked_hints(&public)?;
+
+    let mut unlocked = vec![];
+    for hint in locked.iter_mut().filter(|h| h.hint.is_none()) {
+        let Unlock::Solved(target_hash) = &hint.unlock;
+        let Some(secret) = known_secret(target_hash, secrets, db)? else {
+            continue;
+        };
+        hint.hint = Some(decrypt(&hint.ciphertext, &solve_key(&secret))?);
+        unlocked.push(hint.clone());
+    }
+    if !unlocked.is_empty() {
+        public["locked_hints"] = serde_json::to_value(&locked)?;
+        write_public(public_path, &public)?;
+    }
+    Ok(unlocked)
+}
+
+/// The secret of the puzzle with `target_hash`, if it is known.
+fn known_secret(target_hash: &str, secrets: &[([u8; 32], String)], db: Option<&PuzzleStore>) -> Result<Option<[u8; 32]>> {
+    let wanted = target_hash.trim_start_matches("0x").to_lowercase();
+    if let Some((secret, _)) = secrets.iter().find(|(_, hash)| hash.trim_start_matches("0x").to_lowercase() == wanted) {
+        return Ok(Some(*secret));
+    }
+    let Some(db) = db else {
+        return Ok(None);
+    };
+    let Some(record) = db.find_by_hash(target_hash)? else {
+        return Ok(None);
+    };
+    let Some(solve) = db.solve_for(record.id)? else {
+        return Ok(None);
+    };
+    Ok(solve_secret(&solve))
+}
+
+/// The verified secret revealed by a solve.
+pub fn solve_secret(solve: &crate::solves::SolveInfo) -> Option<[u8; 32]> {
+    if !solve.verified {
+        return None;
+    }
+    let bytes = hex::decode(solve.secret_hex.as_deref()?).ok()?;
+    bytes.try_into().ok()
+}
+
+/// Whether a public file has locked hints still locked.
+pub fn has_locked(public: &serde_json::Value) -> bool {
+    locked_hints(public).is_ok_and(|locked| locked.iter().any(|h| h.hint.is_none()))
+}
+
+fn locked_hints(public: &serde_json::Value) -> Result<Vec<LockedHint>> {
+    match public.get("locked_hints") {
+        Some(locked) if !locked.is_null() => serde_json::from_value(locked.clone()).context("Invalid locked_hints in puzzle file"),
+        _ => Ok(vec![]),
+    }
+}
+
+fn read_public(path: &Path) -> Result<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?)
+        .context("Failed to parse puzzle JSON")
+}
+
+fn write_public(path: &Path, public: &serde_json::Value) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(public)?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Watcher sink that unlocks hints as soon as the puzzle they wait for
+/// is solved, in every public file known to the database.
+pub struct HintUnlockSink {
+    db: PuzzleStore,
+}
+
+impl HintUnlockSink {
+    pub fn new() -> Result<Self> {
+        Ok(HintUnlockSink { db: PuzzleStore::open_default()? })
+    }
+}
+
+impl EventSink for HintUnlockSink {
+    fn emit(&mut self, event: &Event) -> Result<()> {
+        if !matches!(event.kind, EventKind::SolveInMempool | EventKind::SolveConfirmed) {
+            return Ok(());
+        }
+        let Some(secret) = event.solve.as_ref().and_then(solve_secret) else {
+            return Ok(());
+        };
+        let secrets = [(secret, event.target_hash.clone())];
+
+        for puzzle in self.db.list_puzzles()? {
+            let Some(public_file) = &puzzle.public_file else {
+                continue;
+            };
+            let public_path = Path::new(public_file);
+            let waiting = read_public(public_path).is_ok_and(|public| {
+                locked_hints(&public).is_ok_and(|locked| {
+                    locked.iter().any(|h| h.hint.is_none() && h.unlock == Unlock::Solved(event.target_hash.clone()))
+                })
+            });
+            if !waiting {
+                continue;
+            }
+            for hint in unlock(public_path, &secrets, None)? {
+                eprintln!("🔓 Hint of {} unlocked: \"{}\"", puzzle.target_hash, hint.hint.unwrap_or_default());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * OUTPUT - Machine-readable results for scripts and automation
+ *
+ * With --output json a binary prints exactly one JSON object on stdout
+ * and sends its human-readable progress to stderr:
+ *   success: {"ok": true, ...result fields}
+ *   failure: {"ok": false, "error": {"code": "...", "message": "..."}}
+ *
+ * Error codes are stable, so scripts can match on them:
+ *   invalid_arguments     bad command line
+ *   wrong_secret          the secret does not hash to the target hash
+ *   utxo_spent            the puzzle output is spent or does not exist
+ *   missing_blinding_key  a confidential puzzle without its blinding key
+ *   execution_failed      the Simplicity program failed locally
+ *   contract_mismatch     a puzzle file's address is not its contract's
+ *   broadcast_rejected    the node refused the transaction
+ *   error                 anything else
+ *
+ * Binaries print progress with progress!, which goes to stdout in text
+ * mode and to stderr in JSON mode.
+ */
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Print a progress line: stdout in text mode, stderr with --output json.
+#[macro_export]
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if $crate::output::is_json() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Select the output format for the rest of the process.
+pub fn init(format: OutputFormat) {
+    JSON.store(format == OutputFormat::Json, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Stable error codes of the JSON output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidArguments,
+    WrongSecret,
+    UtxoSpent,
+    MissingBlindingKey,
+    ExecutionFailed,
+    ContractMismatch,
+    BroadcastRejected,
+    Error,
+}
+
+/// An error carrying its JSON error code.
+#[derive(Debug)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// An error with a stable code.
+pub fn error(code: ErrorCode, message: impl Into<String>) -> anyhow::Error {
+    CommandError { code, message: message.into() }.into()
+}
+
+/// The code of `err`, or of the first error in its chain that has one.
+pub fn error_code(err: &anyhow::Error) -> ErrorCode {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<CommandError>())
+        .map_or(ErrorCode::Error, |e| e.code)
+}
+
+/// The JSON object reporting `err`.
+pub fn error_object(err: &anyhow::Error) -> serde_json::Value {
+    serde_json::json!({
+        "ok": false,
+        "error": {
+            "code": error_code(err),
+            "message": format!("{:#}", err),
+        },
+    })
+}
+
+/// End a binary: in JSON mode print the result or error object (and exit
+/// non-zero on error); in text mode just return the error, if any.
+pub fn finish(result: Result<serde_json::Value>) -> Result<()> {
+    if !is_json() {
+        return result.map(|_| ());
+    }
+
+    match result {
+        Ok(value) => {
+            let mut object = serde_json::Map::new();
+            object.insert("ok".to_string(), true.into());
+            match value {
+                serde_json::Value::Object(fields) => object.extend(fields),
+                serde_json::Value::Null => {}
+                other => {
+                    object.insert("result".to_string(), other);
+                }
+            }
+            println!("{}", serde_json::to_string_pretty(&object)?);
+            Ok(())
+        }
+        Err(e) => {
+            println!("{}", serde_json::to_string_pretty(&error_object(&e))?);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+/*
+ * PSET - Puzzle spends as Partially Signed Elements Transactions
+ *
+ * solve-puzzle --pset-out writes the spend as a PSET instead of
+ * broadcasting it. The puzzle input carries everything another tool
+ * needs to understand it:
+ *   - witness UTXO (the puzzle output)
+ *   - Taproot leaf script (the CMR), leaf version and control block
+ *   - internal key
+ *   - the finalized Simplicity witness
+ *
+ * The puzzle contract does not commit to the rest of the transaction,
+ * so other wallets (a local Green or LWK instance, the node wallet) may
+ * add their own inputs and outputs, e.g. to pay the fee, and sign them.
+ * finalize-pset then checks that every input is finalized, runs the
+ * Simplicity inputs locally against the combined transaction, and
+ * extracts it for broadcasting.
+ */
+
+use anyhow::{Context, Result};
+use elements::pset::PartiallySignedTransaction as Psbt;
+use elements::secp256k1_zkp::XOnlyPublicKey;
+use elements::taproot::{ControlBlock, LeafVersion};
+use elements::{Script, Transaction, TxInWitness, TxOut};
+use simplicity::jet::Elements;
+use simplicity::{BitIter, Cmr, RedeemNode};
+use std::sync::Arc;
+
+/// Taproot annexes start with this byte.
+const ANNEX_TAG: u8 = 0x50;
+
+/// The Taproot leaf version of Simplicity scripts.
+pub fn simplicity_leaf_version() -> LeafVersion {
+    LeafVersion::from_u8(simplicity::leaf_version().into()).expect("valid leaf version")
+}
+
+/// PSET of a finished spend. `spent_utxos` are the outputs spent by the
+/// inputs of `tx`, in order; input 0 is the puzzle input, spent through
+/// `script` with `control_block`.
+pub fn puzzle_pset(
+    tx: &Transaction,
+    spent_utxos: &[TxOut],
+    script: &Script,
+    control_block: &ControlBlock,
+    internal_key: XOnlyPublicKey,
+) -> Psbt {
+    let mut unsigned = tx.clone();
+    for input in unsigned.input.iter_mut() {
+        input.witness = TxInWitness::empty();
+    }
+
+    let mut pset = Psbt::from_tx(unsigned);
+    for (i, input) in pset.inputs_mut().iter_mut().enumerate() {
+        input.witness_utxo = spent_utxos.get(i).cloned();
+        let witness = &tx.input[i].witness.script_witness;
+        if !witness.is_empty() {
+            input.final_script_witness = Some(witness.clone());
+        }
+    }
+
+    let puzzle_input = &mut pset.inputs_mut()[0];
+    puzzle_input
+        .tap_scripts
+        .insert(control_block.clone(), (script.clone(), simplicity_leaf_version()));
+    puzzle_input.tap_internal_key = Some(internal_key);
+    pset
+}
+
+/// A finalized Simplicity input of a PSET, ready to run locally.
+pub struct SimplicityInput {
+    pub index: usize,
+    pub program: Arc<RedeemNode<Elements>>,
+    pub cmr: Cmr,
+    pub control_block: ControlBlock,
+    pub annex: Option<Vec<u8>>,
+}
+
+/// Whether `input` spends a Simplicity leaf.
+pub fn is_simplicity_input(input: &elements::pset::Input) -> bool {
+    input.tap_scripts.values().any(|(_, version)| *version == simplicity_leaf_version())
+}
+
+/// Decode the Simplicity inputs of `pset` from their finalized witnesses:
+///   [simplicity witness, program, script, control block, (annex)]
+pub fn simplicity_inputs(pset: &Psbt) -> Result<Vec<SimplicityInput>> {
+    let mut found = Vec::new();
+    for (index, input) in pset.inputs().iter().enumerate() {
+        if !is_simplicity_input(input) {
+            continue;
+        }
+        let stack = input
+            .final_script_witness
+            .as_ref()
+            .with_context(|| format!("Simplicity input {} has no finalized witness", index))?;
+
+        let (stack, annex) = match stack.split_last() {
+            Some((last, rest)) if rest.len() == 4 && last.first() == Some(&ANNEX_TAG) => (rest, Some(last.clone())),
+            _ => (stack.as_slice(), None),
+        };
+        let [witness, program, script, control_block] = stack else {
+            return Err(anyhow::anyhow!("Simplicity input {} has a malformed witness stack", index));
+        };
+
+        let program = RedeemNode::<Elements>::decode(
+            BitIter::from(program.iter().copied()),
+            BitIter::from(witness.iter().copied()),
+        )
+        .map_err(|e| anyhow::anyhow!("Simplicity input {}: invalid program: {}", index, e))?;
+        let cmr_bytes: [u8; 32] = script
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Simplicity input {}: script is not a CMR", index))?;
+        let control_block = ControlBlock::from_slice(control_block)
+            .map_err(|e| anyhow::anyhow!("Simplicity input {}: invalid control block: {}", index, e))?;
+
+        found.push(SimplicityInput {
+            index,
+            program,
+            cmr: Cmr::from_byte_array(cmr_bytes),
+            control_block,
+            annex,
+        });
+    }
+    Ok(found)
+}
+
+/// Extract the transaction of a fully finalized `pset`, with the outputs
+/// its inputs spend.
+pub fn finalize(pset: &Psbt) -> Result<(Transaction, Vec<TxOut>)> {
+    let unfinalized: Vec<String> = pset
+        .inputs()
+        .iter()
+        .enumerate()
+        .filter(|(_, input)| input.final_script_witness.is_none() && input.final_script_sig.is_none())
+        .map(|(i, _)| i.to_string())
+        .collect();
+    if !unfinalized.is_empty() {
+        return Err(anyhow::anyhow!(
+            "❌ Inputs {} are not finalized; sign them with the wallet that owns them first",
+            unfinalized.join(", ")
+        ));
+    }
+
+    let spent_utxos = pset
+        .inputs()
+        .iter()
+        .enumerate()
+        .map(|(i, input)| input.witness_utxo.clone().with_context(|| format!("Input {} has no witness UTXO", i)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let tx = pset
+        .extract_tx()
+        .map_err(|e| anyhow::anyhow!("Failed to extract transaction: {:?}", e))?;
+    if !tx.output.iter().any(TxOut::is_fee) {
+        return Err(anyhow::anyhow!("❌ Transaction has no fee output"));
+    }
+    Ok((tx, spent_utxos))
+}
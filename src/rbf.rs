@@ -0,0 +1,163 @@
+/*
+ * RBF - Replace-by-fee for puzzle spends
+ *
+ * Everyone racing for a puzzle spends the same outpoint, so the spend
+ * with the higher fee wins the mempool. solve-puzzle signals RBF and
+ * saves each signed spend in the puzzle database; a spend can then be
+ * rebuilt with a higher fee:
+ *   - by hand with bump-fee
+ *   - automatically by puzzle-watcher --auto-bump when another spend of
+ *     the same outpoint shows up in the mempool
+ *
+ * The Simplicity witness does not commit to the outputs, so bumping
+ * just moves value from the prize output to the fee output and keeps
+ * the witness. Only spends that pay their fee out of an explicit prize
+ * can be bumped this way; wallet-funded fees would need re-signing and
+ * blinded prizes re-blinding.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use crate::fees::{self, MIN_RELAY_FEE_RATE};
+use crate::store::{self, PuzzleStore, SpendRecord};
+use crate::watcher::{Event, EventKind, EventSink};
+use anyhow::{Context, Result};
+use elements::{confidential, Transaction};
+
+/// A copy of `tx` paying `new_fee`, taken from the prize output.
+pub fn bump_fee(tx: &Transaction, new_fee: Amount) -> Result<Transaction> {
+    if tx.input.len() != 1 || tx.output.len() != 2 {
+        return Err(anyhow::anyhow!(
+            "Only spends with one puzzle input, a prize output and a fee output can be bumped"
+        ));
+    }
+    let fee_index = tx.output.iter().position(|o| o.is_fee()).context("Spend has no fee output")?;
+    let prize_index = 1 - fee_index;
+
+    let (confidential::Value::Explicit(old_fee), confidential::Value::Explicit(prize)) =
+        (tx.output[fee_index].value, tx.output[prize_index].value)
+    else {
+        return Err(anyhow::anyhow!("Only spends with an explicit prize output can be bumped"));
+    };
+
+    let extra = new_fee
+        .checked_sub(Amount::from_sat(old_fee))
+        .filter(|extra| *extra > Amount::ZERO)
+        .context("New fee must be higher than the current fee")?;
+    let prize = Amount::from_sat(prize)
+        .checked_sub(extra)
+        .filter(|prize| *prize > Amount::ZERO)
+        .ok_or_else(|| anyhow::anyhow!("A fee of {} sats would use up the whole prize", new_fee.to_sat()))?;
+
+    let mut bumped = tx.clone();
+    bumped.output[fee_index].value = confidential::Value::Explicit(new_fee.to_sat());
+    bumped.output[prize_index].value = confidential::Value::Explicit(prize.to_sat());
+    Ok(bumped)
+}
+
+/// Lowest fee that may replace a spend paying `old_fee` (BIP125 rule 4),
+/// raised to `target_rate` sat/vB.
+pub fn replacement_fee(old_fee: Amount, vsize: usize, target_rate: f64) -> Amount {
+    let increment = (MIN_RELAY_FEE_RATE * vsize as f64).ceil() as u64;
+    let minimum = old_fee.to_sat() + increment.max(1);
+    let target = (target_rate * vsize as f64).ceil() as u64;
+    Amount::from_sat(minimum.max(target))
+}
+
+/// Fee needed for a spend of `vsize` to replace a competing mempool
+/// transaction: more in total and a higher rate.
+pub fn outbid_fee(cli: &ElementsCli, competitor_txid: &str, vsize: usize) -> Result<Amount> {
+    let entry = cli.call_json(&["getmempoolentry", competitor_txid])?;
+    let competitor_fee = Amount::from_rpc(&entry["fees"]["base"]).context("Mempool entry without fee")?;
+    let competitor_vsize = entry["vsize"].as_u64().context("Mempool entry without vsize")? as usize;
+
+    let rate = fees::effective_rate(competitor_fee, competitor_vsize) + MIN_RELAY_FEE_RATE;
+    Ok(replacement_fee(competitor_fee, vsize, rate))
+}
+
+/// Rebuild `spend` with `new_fee`, broadcast it and record it in `db`.
+/// Returns the new txid.
+pub fn rebroadcast(cli: &ElementsCli, db: &PuzzleStore, spend: &SpendRecord, new_fee: Amount) -> Result<String> {
+    let tx: Transaction = elements::encode::deserialize(&hex::decode(&spend.tx_hex)?)
+        .context("Stored spend is not a valid transaction")?;
+    let bumped = bump_fee(&tx, new_fee)?;
+    let tx_hex = hex::encode(elements::encode::serialize(&bumped));
+
+    let mut record = SpendRecord {
+        txid: bumped.txid().to_string(),
+        tx_hex: tx_hex.clone(),
+        fee: new_fee,
+        vsize: fees::discount_vsize(&bumped),
+        status: store::SPEND_BUILT.to_string(),
+        replaces: Some(spend.txid.clone()),
+        ..spend.clone()
+    };
+    db.record_spend(&record)?;
+
+    match cli.call(&["sendrawtransaction", &tx_hex]) {
+        Ok(txid) => {
+            record.status = store::SPEND_BROADCAST.to_string();
+            db.record_spend(&record)?;
+            db.set_spend_status(&spend.txid, store::SPEND_REPLACED)?;
+            Ok(txid)
+        }
+        Err(e) => {
+            db.set_spend_status(&record.txid, store::SPEND_FAILED)?;
+            Err(e.context("Failed to broadcast replacement"))
+        }
+    }
+}
+
+/// Watcher sink that outbids competing spends of puzzles we are solving.
+pub struct AutoBumpSink {
+    cli: ElementsCli,
+    db: PuzzleStore,
+    /// Never pay more than this rate, in sat/vB
+    max_fee_rate: Option<f64>,
+}
+
+impl AutoBumpSink {
+    pub fn new(max_fee_rate: Option<f64>) -> Result<Self> {
+        Ok(AutoBumpSink {
+            cli: ElementsCli::from_config()?,
+            db: PuzzleStore::open_default()?,
+            max_fee_rate,
+        })
+    }
+}
+
+impl EventSink for AutoBumpSink {
+    fn emit(&mut self, event: &Event) -> Result<()> {
+        if event.kind != EventKind::SolveInMempool {
+            return Ok(());
+        }
+        let Some(competitor) = event.solve.as_ref().map(|s| s.txid.as_str()) else {
+            return Ok(());
+        };
+        let Some(spend) = self.db.latest_spend(&event.txid, event.vout)? else {
+            return Ok(());
+        };
+        if self.db.is_own_spend(&event.txid, event.vout, competitor)? {
+            return Ok(());
+        }
+
+        let new_fee = outbid_fee(&self.cli, competitor, spend.vsize)?;
+        if let Some(max_rate) = self.max_fee_rate {
+            if fees::effective_rate(new_fee, spend.vsize) > max_rate {
+                return Err(anyhow::anyhow!(
+                    "Outbidding {} needs {} sats, above --max-fee-rate {} sat/vB",
+                    competitor,
+                    new_fee.to_sat(),
+                    max_rate
+                ));
+            }
+        }
+
+        let txid = rebroadcast(&self.cli, &self.db, &spend, new_fee)?;
+        eprintln!(
+            "⚡ Outbid competing spend {} of {}:{} with {} ({} sats fee)",
+            competitor, event.txid, event.vout, txid, new_fee.to_sat()
+        );
+        Ok(())
+    }
+}
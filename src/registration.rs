@@ -0,0 +1,202 @@
+/*
+ * REGISTRATION - Nicknames proven by a signature of the winning address
+ *
+ * A participant picks a nickname and signs
+ *
+ *   puzzle-hunt nickname <nickname> for <address> at <unix time>
+ *
+ * with the key of the address they will solve to, as a Bitcoin-style
+ * signed message: 65 bytes, base64, recoverable ECDSA over
+ * SHA256d("\x18Bitcoin Signed Message:\n" || len || message). The
+ * organizer recovers the public key and checks that it is the key of the
+ * address, as P2PKH, P2WPKH or P2SH-P2WPKH. Nothing is revealed about the
+ * participant until they register, and only the nickname then.
+ *
+ * Addresses are registered without their blinding key, as solves record
+ * them, so a confidential address and its unconfidential form are the
+ * same participant.
+ *
+ * The time makes every registration distinct: the organizer keeps the
+ * time of the last one accepted for an address and refuses any signed
+ * at or before it, so an old signature cannot be replayed to bring back
+ * a nickname the participant has since changed. Times more than
+ * MAX_CLOCK_SKEW ahead of the organizer's clock are refused too.
+ */
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use bitcoin::hashes::Hash;
+use bitcoin::secp256k1::{Message, Secp256k1};
+use bitcoin::sign_message::{signed_msg_hash, MessageSignature};
+use elements::Address;
+use std::str::FromStr;
+
+/// Longest nickname accepted, in characters.
+pub const MAX_NICKNAME: usize = 32;
+
+/// How far in the future a registration may be signed, in seconds.
+pub const MAX_CLOCK_SKEW: u64 = 600;
+
+/// The message a participant signs, at `signed_at` (Unix time).
+pub fn message(address: &str, nickname: &str, signed_at: u64) -> String {
+    format!("puzzle-hunt nickname {} for {} at {}", nickname, address, signed_at)
+}
+
+/// The current Unix time, to sign a registration with.
+pub fn now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+/// Check that a registration signed at `signed_at` is newer than the
+/// last one accepted for the address, and not from the future.
+pub fn check_signed_at(signed_at: u64, last: Option<u64>) -> Result<()> {
+    if signed_at > now() + MAX_CLOCK_SKEW {
+        return Err(anyhow::anyhow!("The registration is signed in the future ({}); check your clock", signed_at));
+    }
+    if let Some(last) = last.filter(|last| signed_at <= *last) {
+        return Err(anyhow::anyhow!(
+            "The registration is not newer than the one recorded (signed at {}); sign it again",
+            last
+        ));
+    }
+    Ok(())
+}
+
+/// The address as solves record it: without its blinding key.
+pub fn unconfidential(address: &str) -> Result<String> {
+    let address = Address::from_str(address).with_context(|| format!("Invalid address {}", address))?;
+    Ok(address.to_unconfidential().to_string())
+}
+
+pub fn check_nickname(nickname: &str) -> Result<()> {
+    if nickname.trim() != nickname || nickname.is_empty() {
+        return Err(anyhow::anyhow!("A nickname cannot be empty or start or end with spaces"));
+    }
+    if nickname.chars().count() > MAX_NICKNAME {
+        return Err(anyhow::anyhow!("A nickname has at most {} characters", MAX_NICKNAME));
+    }
+    if nickname.chars().any(char::is_control) {
+        return Err(anyhow::anyhow!("A nickname cannot contain control characters"));
+    }
+    Ok(())
+}
+
+/// Check that `signature` signs `nickname` for `address` (unconfidential)
+/// at `signed_at` with the address's key.
+pub fn verify(address: &str, nickname: &str, signed_at: u64, signature: &str) -> Result<()> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(signature.trim())
+        .context("The signature is not base64")?;
+    let signature = MessageSignature::from_slice(&bytes).map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+    let hash = signed_msg_hash(&message(address, nickname, signed_at));
+    let pubkey = signature
+        .recover_pubkey(&Secp256k1::verification_only(), hash)
+        .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+
+    let target = Address::from_str(address).with_context(|| format!("Invalid address {}", address))?;
+    // Segwit signers often flag their key as uncompressed; segwit keys never are
+    let compressed = bitcoin::PublicKey::new(pubkey.inner);
+    let candidates = [
+        Address::p2pkh(&pubkey, None, target.params),
+        Address::p2wpkh(&compressed, None, target.params),
+        Address::p2shwpkh(&compressed, None, target.params),
+    ];
+    if !candidates.iter().any(|candidate| candidate.script_pubkey() == target.script_pubkey()) {
+        return Err(anyhow::anyhow!("The signature is not from the key of {}", address));
+    }
+    Ok(())
+}
+
+/// Sign `nickname` for `address` (unconfidential) at `signed_at` with its
+/// private key.
+pub fn sign(address: &str, nickname: &str, signed_at: u64, key: &bitcoin::PrivateKey) -> Result<String> {
+    let secp = Secp256k1::signing_only();
+    let hash = signed_msg_hash(&message(address, nickname, signed_at));
+    let signature = secp.sign_ecdsa_recoverable(&Message::from_digest(hash.to_byte_array()), &key.inner);
+    let signature = MessageSignature::new(signature, key.compressed);
+    Ok(base64::engine::general_purpose::STANDARD.encode(signature.serialize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elements::AddressParams;
+
+    fn key(byte: u8, compressed: bool) -> bitcoin::PrivateKey {
+        let secret_key = bitcoin::secp256k1::SecretKey::from_slice(&[byte; 32]).unwrap();
+        let mut key = bitcoin::PrivateKey::new(secret_key, bitcoin::Network::Regtest);
+        key.compressed = compressed;
+        key
+    }
+
+    fn pubkey(key: &bitcoin::PrivateKey) -> bitcoin::PublicKey {
+        key.public_key(&Secp256k1::new())
+    }
+
+    fn sign_and_verify(address: &Address, key: &bitcoin::PrivateKey) -> Result<()> {
+        let address = address.to_string();
+        let signature = sign(&address, "alice", 1_700_000_000, key)?;
+        verify(&address, "alice", 1_700_000_000, &signature)
+    }
+
+    #[test]
+    fn verifies_p2pkh_p2wpkh_and_p2sh_p2wpkh_addresses() {
+        let params = &AddressParams::LIQUID_TESTNET;
+        for compressed in [true, false] {
+            let key = key(7, compressed);
+            sign_and_verify(&Address::p2pkh(&pubkey(&key), None, params), &key).unwrap();
+        }
+
+        let key = key(7, true);
+        sign_and_verify(&Address::p2wpkh(&pubkey(&key), None, params), &key).unwrap();
+        sign_and_verify(&Address::p2shwpkh(&pubkey(&key), None, params), &key).unwrap();
+
+        // Segwit signers that flag the key as uncompressed
+        let flagged = bitcoin::PrivateKey { compressed: false, ..key };
+        sign_and_verify(&Address::p2wpkh(&pubkey(&key), None, params), &flagged).unwrap();
+    }
+
+    #[test]
+    fn refuses_signatures_from_another_key_or_for_another_message() {
+        let params = &AddressParams::LIQUID_TESTNET;
+        let key = key(7, true);
+        let address = Address::p2wpkh(&pubkey(&key), None, params);
+
+        let error = sign_and_verify(&address, &self::key(8, true)).unwrap_err();
+        assert_eq!(error.to_string(), format!("The signature is not from the key of {}", address));
+
+        // An uncompressed P2PKH key is another address
+        let uncompressed = Address::p2pkh(&pubkey(&self::key(7, false)), None, params);
+        assert!(sign_and_verify(&uncompressed, &key).is_err());
+
+        let address = address.to_string();
+        let signature = sign(&address, "alice", 1_700_000_000, &key).unwrap();
+        assert!(verify(&address, "mallory", 1_700_000_000, &signature).is_err());
+        assert!(verify(&address, "alice", 1_700_000_001, &signature).is_err());
+        assert!(verify(&address, "alice", 1_700_000_000, "not base64!").is_err());
+    }
+
+    #[test]
+    fn registers_confidential_addresses_unconfidential() {
+        let params = &AddressParams::LIQUID_TESTNET;
+        let key = key(7, true);
+        let blinder = pubkey(&self::key(9, true)).inner;
+        let confidential = Address::p2wpkh(&pubkey(&key), Some(blinder), params);
+        let plain = Address::p2wpkh(&pubkey(&key), None, params);
+        assert_eq!(unconfidential(&confidential.to_string()).unwrap(), plain.to_string());
+    }
+
+    #[test]
+    fn checks_nicknames_and_signing_times() {
+        assert!(check_nickname("alice").is_ok());
+        for nickname in ["", " alice", "alice ", "a\u{7}b", &"a".repeat(MAX_NICKNAME + 1)] {
+            assert!(check_nickname(nickname).is_err(), "{:?}", nickname);
+        }
+
+        let now = now();
+        assert!(check_signed_at(now, None).is_ok());
+        assert!(check_signed_at(now, Some(now - 1)).is_ok());
+        assert!(check_signed_at(now, Some(now)).is_err());
+        assert!(check_signed_at(now + 2 * MAX_CLOCK_SKEW, None).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+/*
+ * SECRET FILE - Encrypted storage for puzzle_XXXX_SECRET.json
+ *
+ * The creator's private record (secret, hash, txid, ...) is encrypted
+ * with age, either with a passphrase or to an X25519 recipient key
+ * (age1...). The file keeps its old name and stays JSON: a small
+ * envelope carries the target hash and address in clear, so listing
+ * tools can pair it with the public puzzle file without decrypting it.
+ *
+ * Key selection when writing:
+ *   PUZZLE_SECRET_RECIPIENT=age1...   encrypt to an X25519 public key
+ *   PUZZLE_SECRET_PASSPHRASE=...      encrypt with a passphrase
+ *   (neither set)                     prompt for a passphrase
+ *
+ * Files written before encryption existed (plain JSON with a "secret"
+ * field) are still readable.
+ */
+
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::secrecy::{Secret, SecretString};
+use age::x25519;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Value of the envelope's "encryption" field.
+pub const ENCRYPTION_FORMAT: &str = "age";
+
+/// Environment variable holding an age X25519 recipient (age1...).
+pub const RECIPIENT_ENV: &str = "PUZZLE_SECRET_RECIPIENT";
+
+/// Environment variable holding the encryption passphrase.
+pub const PASSPHRASE_ENV: &str = "PUZZLE_SECRET_PASSPHRASE";
+
+/// How a secret file is encrypted.
+pub enum SecretKey {
+    Passphrase(SecretString),
+    Recipient(x25519::Recipient),
+}
+
+impl SecretKey {
+    /// Human-readable description, safe to print.
+    pub fn describe(&self) -> String {
+        match self {
+            SecretKey::Passphrase(_) => "passphrase".to_string(),
+            SecretKey::Recipient(recipient) => format!("age recipient {}", recipient),
+        }
+    }
+}
+
+/// Pick the encryption key from the environment, prompting for a
+/// passphrase if nothing is configured.
+pub fn encryption_key_from_env() -> Result<SecretKey> {
+    if let Ok(recipient) = env::var(RECIPIENT_ENV) {
+        let recipient = x25519::Recipient::from_str(recipient.trim())
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", RECIPIENT_ENV, e))?;
+        return Ok(SecretKey::Recipient(recipient));
+    }
+
+    if let Ok(passphrase) = env::var(PASSPHRASE_ENV) {
+        return Ok(SecretKey::Passphrase(Secret::new(passphrase)));
+    }
+
+    let passphrase = rpassword::prompt_password("🔑 Passphrase for the SECRET file: ")
+        .context("Failed to read passphrase")?;
+    let confirm = rpassword::prompt_password("🔑 Confirm passphrase: ")
+        .context("Failed to read passphrase")?;
+    if passphrase != confirm {
+        return Err(anyhow::anyhow!("Passphrases do not match"));
+    }
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!("Passphrase must not be empty"));
+    }
+
+    Ok(SecretKey::Passphrase(Secret::new(passphrase)))
+}
+
+/// Encrypt `private_info` and write it to `path` as an envelope.
+pub fn write_encrypted(path: &Path, private_info: &serde_json::Value, key: &SecretKey) -> Result<()> {
+    let plaintext = serde_json::to_vec(private_info)?;
+
+    let encryptor = match key {
+        SecretKey::Passphrase(passphrase) => age::Encryptor::with_user_passphrase(passphrase.clone()),
+        SecretKey::Recipient(recipient) => {
+            age::Encryptor::with_recipients(vec![Box::new(recipient.clone())])
+                .expect("one recipient is always provided")
+        }
+    };
+
+    let mut ciphertext = vec![];
+    let armored = ArmoredWriter::wrap_output(&mut ciphertext, Format::AsciiArmor)?;
+    let mut writer = encryptor
+        .wrap_output(armored)
+        .map_err(|e| anyhow::anyhow!("Failed to encrypt secret file: {}", e))?;
+    writer.write_all(&plaintext)?;
+    writer.finish()?.finish()?;
+
+    let envelope = serde_json::json!({
+        "encryption": ENCRYPTION_FORMAT,
+        "target_hash": private_info["hash"],
+        "address": private_info["address"],
+        "created_at": private_info["created_at"],
+        "ciphertext": String::from_utf8(ciphertext)?,
+    });
+
+    std::fs::write(path, serde_json::to_string_pretty(&envelope)?)?;
+    Ok(())
+}
+
+/// Whether a parsed secret file is an encrypted envelope.
+pub fn is_encrypted(file: &serde_json::Value) -> bool {
+    file["encryption"].as_str().is_some()
+}
+
+/// Target hash of a secret file, read without decrypting it.
+pub fn target_hash(file: &serde_json::Value) -> Option<&str> {
+    if is_encrypted(file) {
+        file["target_hash"].as_str()
+    } else {
+        file["hash"].as_str()
+    }
+}
+
+/// Read a secret file, decrypting it if needed.
+///
+/// Recipient-encrypted files need `identity_file` (an age key file with
+/// AGE-SECRET-KEY-1... lines). Passphrase files read PUZZLE_SECRET_PASSPHRASE
+/// or prompt for it.
+pub fn read_secret_file(path: &Path, identity_file: Option<&Path>) -> Result<serde_json::Value> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: serde_json::Value = serde_json::from_str(&data)
+        .context("Failed to parse secret file JSON")?;
+
+    if !is_encrypted(&file) {
+        return Ok(file);
+    }
+
+    let format = file["encryption"].as_str().unwrap_or_default();
+    if format != ENCRYPTION_FORMAT {
+        return Err(anyhow::anyhow!("Unsupported secret file encryption: {}", format));
+    }
+    let ciphertext = file["ciphertext"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing ciphertext in secret file"))?;
+
+    let decryptor = age::Decryptor::new(ArmoredReader::new(ciphertext.as_bytes()))
+        .map_err(|e| anyhow::anyhow!("Invalid encrypted secret file: {}", e))?;
+
+    let mut plaintext = vec![];
+    match decryptor {
+        age::Decryptor::Passphrase(decryptor) => {
+            let passphrase = match env::var(PASSPHRASE_ENV) {
+                Ok(passphrase) => Secret::new(passphrase),
+                Err(_) => Secret::new(
+                    rpassword::prompt_password("🔑 Passphrase for the SECRET file: ")
+                        .context("Failed to read passphrase")?,
+                ),
+            };
+            decryptor
+                .decrypt(&passphrase, None)
+                .map_err(|e| anyhow::anyhow!("Failed to decrypt secret file: {}", e))?
+                .read_to_end(&mut plaintext)?;
+        }
+        age::Decryptor::Recipients(decryptor) => {
+            let identity_file = identity_file.ok_or_else(|| {
+                anyhow::anyhow!("This secret file is encrypted to an age key; pass the matching age identity file")
+            })?;
+            let identities = read_identities(identity_file)?;
+            decryptor
+                .decrypt(identities.iter().map(|i| i as &dyn age::Identity))
+                .map_err(|e| anyhow::anyhow!("Failed to decrypt secret file: {}", e))?
+                .read_to_end(&mut plaintext)?;
+        }
+    }
+
+    serde_json::from_slice(&plaintext).context("Decrypted secret file is not valid JSON")
+}
+
+/// Parse the X25519 identities from an age key file, skipping comments.
+fn read_identities(path: &Path) -> Result<Vec<x25519::Identity>> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read identity file {}", path.display()))?;
+
+    let identities = data
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            x25519::Identity::from_str(line)
+                .map_err(|e| anyhow::anyhow!("Invalid identity in {}: {}", path.display(), e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if identities.is_empty() {
+        return Err(anyhow::anyhow!("No identities found in {}", path.display()));
+    }
+
+    Ok(identities)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::secrecy::ExposeSecret;
+    use std::path::PathBuf;
+
+    fn temp_path(test: &str, name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("puzzle-hunt-secret-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    fn private_info() -> serde_json::Value {
+        serde_json::json!({
+            "secret": "correct horse",
+            "hash": "0x8c4f",
+            "address": "tex1pexample",
+            "created_at": "2026-01-01T00:00:00Z",
+        })
+    }
+
+    // The only test touching PUZZLE_SECRET_PASSPHRASE, so tests running
+    // in parallel never see each other's passphrase.
+    #[test]
+    fn round_trips_with_a_passphrase_and_refuses_a_wrong_one() {
+        let path = temp_path("passphrase", "puzzle_SECRET.json");
+        let key = SecretKey::Passphrase(Secret::new("correct passphrase".to_string()));
+        write_encrypted(&path, &private_info(), &key).unwrap();
+
+        let envelope: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(is_encrypted(&envelope));
+        assert_eq!(target_hash(&envelope), Some("0x8c4f"));
+        assert!(!envelope["ciphertext"].as_str().unwrap().contains("correct horse"));
+
+        env::set_var(PASSPHRASE_ENV, "correct passphrase");
+        assert_eq!(read_secret_file(&path, None).unwrap(), private_info());
+
+        env::set_var(PASSPHRASE_ENV, "wrong passphrase");
+        let error = read_secret_file(&path, None).unwrap_err().to_string();
+        env::remove_var(PASSPHRASE_ENV);
+        assert!(error.starts_with("Failed to decrypt secret file"), "{}", error);
+    }
+
+    #[test]
+    fn round_trips_with_an_age_key_and_refuses_another_key() {
+        let identity = x25519::Identity::generate();
+        let path = temp_path("recipient", "puzzle_SECRET.json");
+        write_encrypted(&path, &private_info(), &SecretKey::Recipient(identity.to_public())).unwrap();
+
+        let missing = read_secret_file(&path, None).unwrap_err().to_string();
+        assert!(missing.contains("pass the matching age identity file"), "{}", missing);
+
+        let identity_file = temp_path("recipient", "key.txt");
+        std::fs::write(&identity_file, format!("# created: now\n{}\n", identity.to_string().expose_secret())).unwrap();
+        assert_eq!(read_secret_file(&path, Some(&identity_file)).unwrap(), private_info());
+
+        let other_file = temp_path("recipient", "other.txt");
+        std::fs::write(&other_file, x25519::Identity::generate().to_string().expose_secret()).unwrap();
+        let error = read_secret_file(&path, Some(&other_file)).unwrap_err().to_string();
+        assert!(error.starts_with("Failed to decrypt secret file"), "{}", error);
+    }
+
+    #[test]
+    fn reads_plain_files_from_before_encryption() {
+        let path = temp_path("plain", "puzzle_SECRET.json");
+        std::fs::write(&path, private_info().to_string()).unwrap();
+        let file = read_secret_file(&path, None).unwrap();
+        assert!(!is_encrypted(&file));
+        assert_eq!(target_hash(&file), Some("0x8c4f"));
+    }
+}
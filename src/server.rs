@@ -0,0 +1,447 @@
+/*
+ * SERVER - HTTP JSON API over the puzzle database
+ *
+ * Routes (puzzles are named by target hash or a prefix of it):
+ *
+ *   GET  /health                  network and node status
+ *   GET  /puzzles                 every puzzle with status and current pot
+ *   GET  /puzzles/<hash>          the public puzzle file
+ *   GET  /puzzles/<hash>/program  the compiled program bundle (see export.rs)
+ *   GET  /leaderboard             winners ranked by solves (see leaderboard.rs)
+ *   POST /register                {"address": "...", "nickname": "...",
+ *                                  "signed_at": <unix>, "signature": "<base64>"}
+ *                                 (see register.rs)
+ *   POST /puzzles/<hash>/solve    {"secret": "...", "to": "<address>",
+ *                                  "fee_rate": "2", "dry_run": false, ...}
+ *                                 builds, checks and broadcasts the solve
+ *
+ * Responses use the --output json shapes: {"ok": true, ...} or
+ * {"ok": false, "error": {"code": ..., "message": ...}} with the HTTP
+ * status picked from the error code.
+ *
+ * Nothing from a SECRET file is ever served: only a puzzle's public file
+ * is read, never a file named _SECRET or an encrypted envelope, and
+ * fields that only belong in the SECRET file are dropped.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use crate::commands::{export, register, solve};
+use crate::leaderboard;
+use crate::output::{self, ErrorCode};
+use crate::secret_file;
+use crate::store::{PuzzleRecord, PuzzleStore};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Fields that must never leave the organizer's machine.
+const PRIVATE_FIELDS: &[&str] = &["secret", "hash", "hint_schedule"];
+
+/// Largest request body accepted.
+pub const MAX_BODY: usize = 64 * 1024;
+
+/// An HTTP status and JSON body.
+pub struct Reply {
+    pub status: u16,
+    pub body: serde_json::Value,
+}
+
+impl Reply {
+    fn ok(body: serde_json::Value) -> Self {
+        let mut object = serde_json::Map::new();
+        object.insert("ok".to_string(), true.into());
+        match body {
+            serde_json::Value::Object(fields) => object.extend(fields),
+            other => {
+                object.insert("result".to_string(), other);
+            }
+        }
+        Reply { status: 200, body: object.into() }
+    }
+
+    fn error(err: &anyhow::Error) -> Self {
+        let status = match output::error_code(err) {
+            ErrorCode::InvalidArguments | ErrorCode::WrongSecret | ErrorCode::MissingBlindingKey => 400,
+            ErrorCode::UtxoSpent | ErrorCode::ContractMismatch => 409,
+            ErrorCode::ExecutionFailed => 422,
+            ErrorCode::BroadcastRejected => 502,
+            ErrorCode::Error => 500,
+        };
+        Reply { status, body: output::error_object(err) }
+    }
+
+    fn not_found(what: &str) -> Self {
+        Reply {
+            status: 404,
+            body: serde_json::json!({ "ok": false, "error": { "code": "not_found", "message": format!("{} not found", what) } }),
+        }
+    }
+}
+
+/// Body of POST /register.
+#[derive(Debug, Deserialize)]
+struct RegisterRequest {
+    address: String,
+    nickname: String,
+    signed_at: u64,
+    signature: String,
+}
+
+/// Body of POST /puzzles/<hash>/solve.
+#[derive(Debug, Deserialize)]
+struct SolveRequest {
+    secret: String,
+    to: String,
+    #[serde(default)]
+    blinding_key: Option<String>,
+    #[serde(default)]
+    fee_rate: Option<String>,
+    #[serde(default)]
+    fee: Option<String>,
+    #[serde(default)]
+    outpoint: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+pub struct Api {
+    cli: ElementsCli,
+    db: PuzzleStore,
+}
+
+impl Api {
+    pub fn new(cli: ElementsCli, db: PuzzleStore) -> Self {
+        Api { cli, db }
+    }
+
+    /// Answer one request.
+    pub fn handle(&self, method: &str, url: &str, body: &str) -> Reply {
+        let path = url.split('?').next().unwrap_or_default().trim_end_matches('/');
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        let result = match (method, segments.as_slice()) {
+            ("GET", ["health"]) => Ok(Some(self.health())),
+            ("GET", ["puzzles"]) => self.list().map(Some),
+            ("GET", ["puzzles", id]) => self.with_puzzle(id, public_puzzle),
+            ("GET", ["puzzles", id, "program"]) => {
+                self.with_puzzle(id, |puzzle| export::bundle(&public_puzzle(puzzle)?))
+            }
+            ("GET", ["leaderboard"]) => self.leaderboard().map(Some),
+            ("POST", ["register"]) => self.register(body).map(Some),
+            ("POST", ["puzzles", id, "solve"]) => self.with_puzzle(id, |puzzle| self.solve(puzzle, body)),
+            _ => return Reply::not_found(&format!("{} {}", method, path)),
+        };
+
+        match result {
+            Ok(Some(body)) => Reply::ok(body),
+            Ok(None) => Reply::not_found("Puzzle"),
+            Err(e) => Reply::error(&e),
+        }
+    }
+
+    fn health(&self) -> serde_json::Value {
+        serde_json::json!({
+            "network": self.cli.chain(),
+            "node_available": self.cli.is_available(),
+        })
+    }
+
+    fn with_puzzle(
+        &self,
+        id: &str,
+        f: impl FnOnce(&PuzzleRecord) -> Result<serde_json::Value>,
+    ) -> Result<Option<serde_json::Value>> {
+        let hex = id.trim_start_matches("0x");
+        if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(output::error(ErrorCode::InvalidArguments, format!("Invalid puzzle hash {}", id)));
+        }
+        match self.db.find_by_prefix(hex)? {
+            Some(puzzle) => f(&puzzle).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn list(&self) -> Result<serde_json::Value> {
+        let node_available = self.cli.is_available();
+        let mut puzzles = vec![];
+        for puzzle in self.db.list_puzzles()? {
+            let pot = if node_available { Some(self.pot(&puzzle)?) } else { None };
+            let public = public_puzzle(&puzzle).unwrap_or_default();
+            puzzles.push(serde_json::json!({
+                "target_hash": puzzle.target_hash,
+                "address": puzzle.address,
+                "network": puzzle.network,
+                "contract": puzzle.contract,
+                "status": puzzle.status,
+                "amount_sats": puzzle.amount_sats,
+                "pot_sats": pot,
+                "hint": puzzle.hint,
+                "hints": public["hints"],
+                "created_at": puzzle.created_at,
+                "solve": self.db.solve_for(puzzle.id)?,
+            }));
+        }
+        Ok(serde_json::json!({ "puzzles": puzzles }))
+    }
+
+    fn leaderboard(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(leaderboard::build(&self.db, &[])?)?)
+    }
+
+    /// What the puzzle's unspent funding outputs hold now.
+    fn pot(&self, puzzle: &PuzzleRecord) -> Result<Amount> {
+        let mut pot = Amount::ZERO;
+        for outpoint in self.db.funding_outpoints(puzzle.id)? {
+            if self.cli.get_tx_out(&outpoint.txid, outpoint.vout)?.is_none() {
+                continue;
+            }
+            let amount = outpoint.amount_sats.unwrap_or(Amount::ZERO);
+            pot = pot.checked_add(amount).context("Pot overflows")?;
+        }
+        Ok(pot)
+    }
+
+    fn register(&self, body: &str) -> Result<serde_json::Value> {
+        let request: RegisterRequest = serde_json::from_str(body)
+            .map_err(|e| output::error(ErrorCode::InvalidArguments, format!("Invalid registration: {}", e)))?;
+        register::register(&self.db, &request.address, &request.nickname, request.signed_at, &request.signature)
+    }
+
+    fn solve(&self, puzzle: &PuzzleRecord, body: &str) -> Result<serde_json::Value> {
+        let request: SolveRequest = serde_json::from_str(body)
+            .map_err(|e| output::error(ErrorCode::InvalidArguments, format!("Invalid solve request: {}", e)))?;
+        let public_file = public_file(puzzle)?;
+
+        solve::run(solve::SolveArgs {
+            puzzle: public_file,
+            secret: request.secret,
+            to: request.to,
+            blinding_key: request.blinding_key,
+            fee_rate: request.fee_rate,
+            fee: request.fee,
+            fee_from_wallet: false,
+            dry_run: request.dry_run,
+            pset_out: None,
+            outpoint: request.outpoint,
+            funding_tx: None,
+            genesis_hash: None,
+        })
+    }
+}
+
+/// The public file of a puzzle, refusing anything that could be a SECRET file.
+fn public_file(puzzle: &PuzzleRecord) -> Result<String> {
+    let file = puzzle
+        .public_file
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("Puzzle {} has no public file", puzzle.target_hash))?;
+    if file.contains("_SECRET") || Some(&file) == puzzle.secret_file.as_ref() {
+        return Err(anyhow::anyhow!("Refusing to serve {}", file));
+    }
+    Ok(file)
+}
+
+/// The public puzzle file's contents, without private fields.
+pub fn public_puzzle(puzzle: &PuzzleRecord) -> Result<serde_json::Value> {
+    let file = public_file(puzzle)?;
+    let mut public: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file))?,
+    )
+    .context("Failed to parse puzzle JSON")?;
+    if secret_file::is_encrypted(&public) {
+        return Err(anyhow::anyhow!("Refusing to serve {}", file));
+    }
+    if let Some(object) = public.as_object_mut() {
+        for field in PRIVATE_FIELDS {
+            object.remove(*field);
+        }
+    }
+    Ok(public)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registration;
+    use std::path::{Path, PathBuf};
+
+    const TARGET_HASH: &str = "0x2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    /// A scratch directory for one test's puzzle files.
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("puzzle-hunt-server-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A puzzle file that also carries the fields only a SECRET file should have.
+    fn write_puzzle_file(dir: &Path) -> PathBuf {
+        let path = dir.join("puzzle_2cf24dba.json");
+        let puzzle = serde_json::json!({
+            "target_hash": TARGET_HASH,
+            "address": "tex1pqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqsw9y2n",
+            "network": "liquidtestnet",
+            "txid": "11".repeat(32),
+            "vout": 0,
+            "amount": "0.1",
+            "hint": "A greeting",
+            "hints": [],
+            "secret": "hello",
+            "hash": TARGET_HASH,
+            "hint_schedule": [{ "at": 1200, "hint": "Five letters" }],
+        });
+        std::fs::write(&path, serde_json::to_string_pretty(&puzzle).unwrap()).unwrap();
+        path
+    }
+
+    /// An API over an in-memory database holding the puzzle in `dir`,
+    /// with no node behind it.
+    fn offline_api(dir: &Path) -> Api {
+        std::env::set_var("ELEMENTS_CLI_PATH", "/nonexistent/elements-cli");
+        let db = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        db.import_json_file(&write_puzzle_file(dir)).unwrap();
+        Api::new(ElementsCli::from_config().unwrap(), db)
+    }
+
+    fn error_code(reply: &Reply) -> &str {
+        reply.body["error"]["code"].as_str().unwrap_or_default()
+    }
+
+    #[test]
+    fn routes_known_paths() {
+        let api = offline_api(&scratch_dir("routes"));
+
+        let health = api.handle("GET", "/health", "");
+        assert_eq!(health.status, 200);
+        assert_eq!(health.body["ok"], true);
+        assert_eq!(health.body["node_available"], false);
+
+        let list = api.handle("GET", "/puzzles/?limit=1", "");
+        assert_eq!(list.status, 200);
+        assert_eq!(list.body["puzzles"][0]["target_hash"], TARGET_HASH);
+        assert!(list.body["puzzles"][0]["pot_sats"].is_null());
+
+        assert_eq!(api.handle("GET", "/puzzles/2cf24dba", "").status, 200);
+        assert_eq!(api.handle("GET", "/puzzles/0x2CF24DBA", "").status, 200);
+
+        // Compiling the program is covered by tests/server.rs
+        let program = api.handle("GET", "/puzzles/2cf24dba/program", "");
+        assert_ne!(error_code(&program), "not_found");
+        assert_eq!(api.handle("GET", "/leaderboard", "").status, 200);
+    }
+
+    #[test]
+    fn rejects_unknown_routes_and_puzzles() {
+        let api = offline_api(&scratch_dir("unknown"));
+
+        for (method, url) in [("GET", "/"), ("GET", "/secrets"), ("POST", "/puzzles"), ("DELETE", "/puzzles/2cf24dba")] {
+            let reply = api.handle(method, url, "");
+            assert_eq!(reply.status, 404, "{} {}", method, url);
+            assert_eq!(error_code(&reply), "not_found");
+        }
+
+        let unknown = api.handle("GET", "/puzzles/deadbeef", "");
+        assert_eq!(unknown.status, 404);
+        assert_eq!(unknown.body["error"]["message"], "Puzzle not found");
+
+        let invalid = api.handle("GET", "/puzzles/not-a-hash", "");
+        assert_eq!(invalid.status, 400);
+        assert_eq!(error_code(&invalid), "invalid_arguments");
+    }
+
+    #[test]
+    fn rejects_malformed_post_bodies() {
+        let api = offline_api(&scratch_dir("bodies"));
+
+        let solve = api.handle("POST", "/puzzles/2cf24dba/solve", "{\"secret\": \"hello\"}");
+        assert_eq!(solve.status, 400);
+        assert_eq!(error_code(&solve), "invalid_arguments");
+
+        let register = api.handle("POST", "/register", "not json");
+        assert_eq!(register.status, 400);
+        assert_eq!(error_code(&register), "invalid_arguments");
+    }
+
+    #[test]
+    fn refuses_replayed_registrations() {
+        let api = offline_api(&scratch_dir("replay"));
+        let secret_key = bitcoin::secp256k1::SecretKey::from_slice(&[7; 32]).unwrap();
+        let key = bitcoin::PrivateKey::new(secret_key, bitcoin::Network::Regtest);
+        let pubkey = key.public_key(&bitcoin::secp256k1::Secp256k1::new());
+        let address = elements::Address::p2wpkh(&pubkey, None, &elements::AddressParams::LIQUID_TESTNET).to_string();
+        let registration = |nickname: &str, signed_at: u64| {
+            let signature = registration::sign(&address, nickname, signed_at, &key).unwrap();
+            serde_json::json!({ "address": address, "nickname": nickname, "signed_at": signed_at, "signature": signature })
+                .to_string()
+        };
+
+        let now = registration::now();
+        let first = registration("alice", now - 20);
+        assert_eq!(api.handle("POST", "/register", &first).status, 200);
+        assert_eq!(api.handle("POST", "/register", &registration("bob", now - 10)).status, 200);
+
+        // The old signature would bring the old nickname back
+        let replay = api.handle("POST", "/register", &first);
+        assert_eq!(replay.status, 400);
+        assert_eq!(error_code(&replay), "invalid_arguments");
+        assert_eq!(api.db.nickname(&address).unwrap().as_deref(), Some("bob"));
+
+        let future = api.handle("POST", "/register", &registration("carol", now + 2 * registration::MAX_CLOCK_SKEW));
+        assert_eq!(future.status, 400);
+
+        // A signature for another time does not verify
+        let mut forged: serde_json::Value = serde_json::from_str(&first).unwrap();
+        forged["signed_at"] = now.into();
+        assert_eq!(api.handle("POST", "/register", &forged.to_string()).status, 400);
+    }
+
+    #[test]
+    fn serves_puzzles_without_private_fields() {
+        let api = offline_api(&scratch_dir("private"));
+
+        let reply = api.handle("GET", "/puzzles/2cf24dba", "");
+        assert_eq!(reply.status, 200);
+        for field in PRIVATE_FIELDS {
+            assert!(reply.body.get(*field).is_none(), "{} was served", field);
+        }
+        assert_eq!(reply.body["target_hash"], TARGET_HASH);
+        assert_eq!(reply.body["hint"], "A greeting");
+    }
+
+    #[test]
+    fn public_puzzle_strips_private_fields() {
+        let dir = scratch_dir("public");
+        let db = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        db.import_json_file(&write_puzzle_file(&dir)).unwrap();
+        let puzzle = db.find_by_prefix("2cf24dba").unwrap().unwrap();
+
+        let public = public_puzzle(&puzzle).unwrap();
+        for field in ["secret", "hash", "hint_schedule"] {
+            assert!(public.get(field).is_none(), "{} was kept", field);
+        }
+        assert_eq!(public["target_hash"], TARGET_HASH);
+        assert_eq!(public["vout"], 0);
+    }
+
+    #[test]
+    fn public_puzzle_refuses_secret_files() {
+        let dir = scratch_dir("refuse");
+        let db = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        db.import_json_file(&write_puzzle_file(&dir)).unwrap();
+        let mut puzzle = db.find_by_prefix("2cf24dba").unwrap().unwrap();
+
+        let secret_path = dir.join("puzzle_2cf24dba_SECRET.json");
+        std::fs::write(&secret_path, "{}").unwrap();
+        puzzle.public_file = Some(secret_path.display().to_string());
+        assert!(public_puzzle(&puzzle).is_err());
+
+        // An encrypted envelope under a public name
+        let envelope = dir.join("puzzle_envelope.json");
+        std::fs::write(&envelope, r#"{"encryption": "age-passphrase", "target_hash": "0x00"}"#).unwrap();
+        puzzle.public_file = Some(envelope.display().to_string());
+        assert!(public_puzzle(&puzzle).is_err());
+
+        puzzle.public_file = None;
+        assert!(public_puzzle(&puzzle).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+/*
+ * SOLVES - Find the transaction that solved a puzzle
+ *
+ * gettxout only says an outpoint is gone. To learn who won, we look
+ * for the transaction spending it: in the mempool when the outpoint is
+ * still unspent in the chain, otherwise block by block from the funding
+ * transaction's height up to the tip. Each outpoint's scan is saved in
+ * the database and resumes from the last block it read.
+ *
+ * A puzzle spend carries the Taproot script-path witness built by
+ * solve-puzzle:
+ *   [simplicity witness, simplicity program, script, control block]
+ * The only witness value of puzzle_jackpot is SECRET (u256), so the
+ * first 32 bytes of the Simplicity witness are the revealed secret.
+ */
+
+use crate::chain::ElementsCli;
+use crate::store::PuzzleStore;
+use anyhow::Result;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// A puzzle's solving transaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct SolveInfo {
+    pub txid: String,
+    /// Revealed secret as 32 bytes of hex, if the witness had one
+    pub secret_hex: Option<String>,
+    /// The secret as text, when it is printable
+    pub secret_text: Option<String>,
+    /// Whether SHA256(secret) matches the puzzle's target hash
+    pub verified: bool,
+    /// Address of the first non-fee output
+    pub destination: Option<String>,
+    /// None while the solve is still in the mempool
+    pub block_height: Option<u64>,
+    pub block_time: Option<i64>,
+    /// Registered nickname of the destination (see registration.rs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner: Option<String>,
+}
+
+/// Find the transaction spending `funding_txid:vout`, if any.
+///
+/// gettxout tells where to look: an outpoint still unspent in the chain
+/// can only be spent in the mempool. A block scan resumes after the last
+/// block scanned for this outpoint (see PuzzleStore::scan_marker), so
+/// repeated calls only read new blocks.
+pub fn find_solve(
+    cli: &ElementsCli,
+    db: &PuzzleStore,
+    funding_txid: &str,
+    vout: u32,
+    target_hash: &str,
+) -> Result<Option<SolveInfo>> {
+    if cli.get_tx_out(funding_txid, vout)?.is_some() {
+        return Ok(None);
+    }
+    let Some(funding_height) = funding_height(cli, funding_txid)? else {
+        // An unconfirmed funding transaction can only be spent in the mempool
+        return mempool_spend(cli, funding_txid, vout, target_hash);
+    };
+    let unspent_in_chain = !cli
+        .call_json(&["gettxout", funding_txid, &vout.to_string(), "false"])?
+        .is_null();
+    if unspent_in_chain {
+        return mempool_spend(cli, funding_txid, vout, target_hash);
+    }
+
+    // Resume after the last scanned block, unless it was reorged out
+    let start = match db.scan_marker(funding_txid, vout)? {
+        Some((height, hash)) if cli.call(&["getblockhash", &height.to_string()]).ok().as_ref() == Some(&hash) => height + 1,
+        _ => funding_height,
+    };
+    let tip: u64 = cli.call(&["getblockcount"])?.parse()?;
+
+    let mut last = None;
+    for height in start..=tip {
+        let hash = cli.call(&["getblockhash", &height.to_string()])?;
+        let block = cli.call_json(&["getblock", &hash, "2"])?;
+        let time = block["time"].as_i64();
+
+        for tx in block["tx"].as_array().into_iter().flatten() {
+            if let Some(input) = spending_input(tx, funding_txid, vout) {
+                return Ok(Some(solve_from_tx(tx, input, target_hash, Some(height), time)));
+            }
+        }
+        last = Some((height, hash));
+    }
+
+    if let Some((height, hash)) = last {
+        db.set_scan_marker(funding_txid, vout, height, &hash)?;
+    }
+    Ok(None)
+}
+
+/// The mempool transaction spending `funding_txid:vout`, if any.
+fn mempool_spend(cli: &ElementsCli, funding_txid: &str, vout: u32, target_hash: &str) -> Result<Option<SolveInfo>> {
+    let mempool = cli.call_json(&["getrawmempool"])?;
+    for txid in mempool.as_array().into_iter().flatten().filter_map(|t| t.as_str()) {
+        let Ok(tx) = cli.get_raw_transaction(txid) else {
+            continue;
+        };
+        if let Some(input) = spending_input(&tx, funding_txid, vout) {
+            return Ok(Some(solve_from_tx(&tx, input, target_hash, None, None)));
+        }
+    }
+    Ok(None)
+}
+
+/// Height of the block containing `txid`, from the node or the wallet.
+fn funding_height(cli: &ElementsCli, txid: &str) -> Result<Option<u64>> {
+    let confirmations = match cli.get_raw_transaction(txid) {
+        Ok(tx) => tx["confirmations"].as_u64(),
+        // Without -txindex only wallet transactions can be looked up
+        Err(_) => cli
+            .call_wallet_json(&["gettransaction", txid])
+            .ok()
+            .and_then(|tx| tx["confirmations"].as_i64())
+            .filter(|c| *c > 0)
+            .map(|c| c as u64),
+    };
+
+    let Some(confirmations) = confirmations.filter(|c| *c > 0) else {
+        return Ok(None);
+    };
+    let tip: u64 = cli.call(&["getblockcount"])?.parse()?;
+    Ok(Some(tip + 1 - confirmations))
+}
+
+/// Index of the input of `tx` spending `txid:vout`.
+pub fn spending_input(tx: &serde_json::Value, txid: &str, vout: u32) -> Option<usize> {
+    tx["vin"].as_array()?.iter().position(|input| {
+        input["txid"].as_str() == Some(txid) && input["vout"].as_u64() == Some(vout as u64)
+    })
+}
+
+/// Build a SolveInfo from a verbose transaction and the input that spent the puzzle.
+pub fn solve_from_tx(
+    tx: &serde_json::Value,
+    input: usize,
+    target_hash: &str,
+    block_height: Option<u64>,
+    block_time: Option<i64>,
+) -> SolveInfo {
+    let witness: Vec<Vec<u8>> = tx["vin"][input]["txinwitness"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().and_then(|h| hex::decode(h).ok()))
+        .collect();
+
+    let secret = extract_secret(&witness);
+    let verified = secret.is_some_and(|s| secret_matches(&s, target_hash));
+
+    let destination = tx["vout"].as_array().into_iter().flatten().find_map(|output| {
+        let script = &output["scriptPubKey"];
+        if script["type"].as_str() == Some("fee") {
+            return None;
+        }
+        script["address"].as_str().map(str::to_string)
+    });
+
+    SolveInfo {
+        txid: tx["txid"].as_str().unwrap_or_default().to_string(),
+        secret_hex: secret.map(hex::encode),
+        secret_text: secret.as_ref().and_then(secret_as_text),
+        verified,
+        destination,
+        block_height,
+        block_time,
+        winner: None,
+    }
+}
+
+/// The SECRET witness value from a Simplicity script-path witness stack.
+pub fn extract_secret(witness: &[Vec<u8>]) -> Option<[u8; 32]> {
+    // [simplicity witness, program, script, control block], optionally an annex
+    if witness.len() < 4 {
+        return None;
+    }
+    let simplicity_witness = &witness[0];
+    if simplicity_witness.len() < 32 {
+        return None;
+    }
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&simplicity_witness[..32]);
+    Some(secret)
+}
+
+/// Whether SHA256(secret) equals `target_hash` (with or without 0x).
+pub fn secret_matches(secret: &[u8; 32], target_hash: &str) -> bool {
+    let hash = hex::encode(Sha256::digest(secret));
+    hash == target_hash.trim_start_matches("0x").to_lowercase()
+}
+
+/// Text secrets are stored left-padded with zeros, see create-puzzle.
+pub fn secret_as_text(secret: &[u8; 32]) -> Option<String> {
+    let start = secret.iter().position(|b| *b != 0)?;
+    let text = std::str::from_utf8(&secret[start..]).ok()?;
+    if text.chars().all(|c| !c.is_control()) {
+        Some(text.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A text secret right-aligned in 32 bytes, as create-puzzle pads it.
+    fn text_secret(text: &str) -> [u8; 32] {
+        let mut secret = [0u8; 32];
+        secret[32 - text.len()..].copy_from_slice(text.as_bytes());
+        secret
+    }
+
+    fn target_hash(secret: &[u8; 32]) -> String {
+        hex::encode(Sha256::digest(secret))
+    }
+
+    /// A solve-puzzle witness stack revealing `secret`.
+    fn witness(secret: &[u8; 32]) -> Vec<Vec<u8>> {
+        let mut simplicity_witness = secret.to_vec();
+        simplicity_witness.push(0x80);
+        vec![simplicity_witness, vec![1; 40], vec![2; 32], vec![3; 33]]
+    }
+
+    #[test]
+    fn extracts_the_secret_from_the_witness() {
+        let secret = text_secret("satoshi");
+        assert_eq!(extract_secret(&witness(&secret)), Some(secret));
+        assert_eq!(extract_secret(&witness(&secret)[..3]), None);
+        assert_eq!(extract_secret(&[vec![0; 31], vec![], vec![], vec![]]), None);
+    }
+
+    #[test]
+    fn verifies_the_secret_against_the_target_hash() {
+        let secret = text_secret("satoshi");
+        let target_hash = target_hash(&secret);
+        assert!(secret_matches(&secret, &target_hash));
+        assert!(secret_matches(&secret, &format!("0x{}", target_hash.to_uppercase())));
+        assert!(!secret_matches(&text_secret("satoshj"), &target_hash));
+        assert_eq!(secret_as_text(&secret).as_deref(), Some("satoshi"));
+        assert_eq!(secret_as_text(&[0; 32]), None);
+    }
+
+    #[test]
+    fn reads_a_solve_from_a_verbose_transaction() {
+        let secret = text_secret("satoshi");
+        let target_hash = format!("0x{}", target_hash(&secret));
+        let tx = serde_json::json!({
+            "txid": "ab".repeat(32),
+            "vin": [
+                { "txid": "11".repeat(32), "vout": 1, "txinwitness": [] },
+                { "txid": "22".repeat(32), "vout": 0, "txinwitness": witness(&secret).iter().map(hex::encode).collect::<Vec<_>>() },
+            ],
+            "vout": [
+                { "scriptPubKey": { "type": "fee" } },
+                { "scriptPubKey": { "type": "witness_v0_keyhash", "address": "tex1winner" } },
+            ],
+        });
+
+        let input = spending_input(&tx, &"22".repeat(32), 0).unwrap();
+        assert_eq!(input, 1);
+        let solve = solve_from_tx(&tx, input, &target_hash, Some(7), None);
+        assert!(solve.verified);
+        assert_eq!(solve.secret_text.as_deref(), Some("satoshi"));
+        assert_eq!(solve.destination.as_deref(), Some("tex1winner"));
+        assert_eq!(solve.block_height, Some(7));
+    }
+}
@@ -0,0 +1,991 @@
+/*
+ * STORE - Local SQLite database of puzzles
+ *
+ * Replaces scanning loose puzzle_*.json files. One row per puzzle,
+ * keyed by target hash, plus:
+ *   - funding_outpoints: every outpoint that pays the puzzle address
+ *   - status_history:    every status change, with a note
+ *   - solve_transactions: transactions that spent a puzzle
+ *   - pot_additions:     extra funds sent to a puzzle after creation
+ *   - events:            lifecycle events seen by puzzle-watcher
+ *   - participants:      nicknames registered for winning addresses
+ *
+ * The database lives in puzzles.db in the working directory, or at
+ * the path in PUZZLE_DB. Existing JSON files can be imported with
+ * `puzzle-db import`.
+ */
+
+use crate::amount::Amount;
+use crate::solves::{self, SolveInfo};
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the database location.
+pub const DB_PATH_ENV: &str = "PUZZLE_DB";
+
+/// Database file used when PUZZLE_DB is not set.
+pub const DEFAULT_DB_PATH: &str = "puzzles.db";
+
+/// Schema migrations, applied in order. PRAGMA user_version records how
+/// many have run, so only append to this list.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE puzzles (
+        id INTEGER PRIMARY KEY,
+        target_hash TEXT NOT NULL UNIQUE,
+        address TEXT NOT NULL,
+        network TEXT NOT NULL DEFAULT 'liquidtestnet',
+        contract TEXT NOT NULL DEFAULT 'puzzle_jackpot',
+        amount TEXT,
+        hint TEXT,
+        status TEXT NOT NULL DEFAULT 'unknown',
+        created_at TEXT,
+        public_file TEXT,
+        secret_file TEXT
+    );
+    CREATE TABLE funding_outpoints (
+        id INTEGER PRIMARY KEY,
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+        txid TEXT NOT NULL,
+        vout INTEGER NOT NULL,
+        amount TEXT,
+        asset TEXT,
+        spent_by TEXT,
+        UNIQUE(txid, vout)
+    );
+    CREATE TABLE status_history (
+        id INTEGER PRIMARY KEY,
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+        status TEXT NOT NULL,
+        note TEXT,
+        recorded_at TEXT NOT NULL
+    );
+    CREATE TABLE solve_transactions (
+        id INTEGER PRIMARY KEY,
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+        txid TEXT NOT NULL UNIQUE,
+        destination TEXT,
+        recorded_at TEXT NOT NULL
+    );
+    CREATE TABLE pot_additions (
+        id INTEGER PRIMARY KEY,
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+        txid TEXT NOT NULL,
+        vout INTEGER NOT NULL,
+        amount TEXT,
+        added_at TEXT NOT NULL,
+        UNIQUE(txid, vout)
+    );",
+    // One transaction can solve several puzzles, so a solve is keyed by both
+    "CREATE TABLE solve_transactions_new (
+        id INTEGER PRIMARY KEY,
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+        txid TEXT NOT NULL,
+        destination TEXT,
+        secret TEXT,
+        secret_verified INTEGER NOT NULL DEFAULT 0,
+        block_height INTEGER,
+        block_time INTEGER,
+        recorded_at TEXT NOT NULL,
+        UNIQUE(puzzle_id, txid)
+    );
+    INSERT INTO solve_transactions_new (id, puzzle_id, txid, destination, recorded_at)
+        SELECT id, puzzle_id, txid, destination, recorded_at FROM solve_transactions;
+    DROP TABLE solve_transactions;
+    ALTER TABLE solve_transactions_new RENAME TO solve_transactions;
+    ALTER TABLE funding_outpoints ADD COLUMN scanned_height INTEGER;
+    ALTER TABLE funding_outpoints ADD COLUMN scanned_block TEXT;",
+    "CREATE TABLE events (
+        id INTEGER PRIMARY KEY,
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles(id),
+        kind TEXT NOT NULL,
+        txid TEXT,
+        vout INTEGER,
+        block_height INTEGER,
+        payload TEXT NOT NULL,
+        recorded_at TEXT NOT NULL
+    );",
+    // Amounts were 8-decimal L-BTC text; keep them as whole satoshis
+    "ALTER TABLE puzzles ADD COLUMN amount_sats INTEGER;
+    UPDATE puzzles SET amount_sats = CASE
+            WHEN instr(amount, '.') = 0 THEN CAST(amount AS INTEGER) * 100000000
+            ELSE CAST(substr(amount, 1, instr(amount, '.') - 1) AS INTEGER) * 100000000
+                + CAST(substr(substr(amount, instr(amount, '.') + 1) || '00000000', 1, 8) AS INTEGER)
+        END
+        WHERE amount <> '' AND amount NOT GLOB '*[^0-9.]*';
+    ALTER TABLE puzzles DROP COLUMN amount;
+    ALTER TABLE funding_outpoints ADD COLUMN amount_sats INTEGER;
+    UPDATE funding_outpoints SET amount_sats = CASE
+            WHEN instr(amount, '.') = 0 THEN CAST(amount AS INTEGER) * 100000000
+            ELSE CAST(substr(amount, 1, instr(amount, '.') - 1) AS INTEGER) * 100000000
+                + CAST(substr(substr(amount, instr(amount, '.') + 1) || '00000000', 1, 8) AS INTEGER)
+        END
+        WHERE amount <> '' AND amount NOT GLOB '*[^0-9.]*';
+    ALTER TABLE funding_outpoints DROP COLUMN amount;
+    ALTER TABLE pot_additions ADD COLUMN amount_sats INTEGER;
+    UPDATE pot_additions SET amount_sats = CASE
+            WHEN instr(amount, '.') = 0 THEN CAST(amount AS INTEGER) * 100000000
+            ELSE CAST(substr(amount, 1, instr(amount, '.') - 1) AS INTEGER) * 100000000
+                + CAST(substr(substr(amount, instr(amount, '.') + 1) || '00000000', 1, 8) AS INTEGER)
+        END
+        WHERE amount <> '' AND amount NOT GLOB '*[^0-9.]*';
+    ALTER TABLE pot_additions DROP COLUMN amount;",
+    "CREATE TABLE spends (
+        id INTEGER PRIMARY KEY,
+        funding_txid TEXT NOT NULL,
+        funding_vout INTEGER NOT NULL,
+        txid TEXT NOT NULL UNIQUE,
+        tx_hex TEXT NOT NULL,
+        fee_sats INTEGER NOT NULL,
+        vsize INTEGER NOT NULL,
+        destination TEXT,
+        status TEXT NOT NULL,
+        replaces TEXT,
+        created_at TEXT NOT NULL
+    );",
+    "CREATE TABLE participants (
+        id INTEGER PRIMARY KEY,
+        address TEXT NOT NULL UNIQUE,
+        nickname TEXT NOT NULL COLLATE NOCASE UNIQUE,
+        signature TEXT NOT NULL,
+        registered_at TEXT NOT NULL
+    );",
+    "ALTER TABLE participants ADD COLUMN signed_at INTEGER NOT NULL DEFAULT 0;",
+];
+
+/// Puzzle lifecycle status.
+pub const STATUS_ACTIVE: &str = "active";
+pub const STATUS_SOLVED: &str = "solved";
+pub const STATUS_UNKNOWN: &str = "unknown";
+pub const STATUS_ARCHIVED: &str = "archived";
+
+/// Every puzzle status, e.g. for command-line filters.
+pub const STATUSES: [&str; 4] = [STATUS_ACTIVE, STATUS_SOLVED, STATUS_UNKNOWN, STATUS_ARCHIVED];
+
+/// Status of one of our own signed spends.
+pub const SPEND_BUILT: &str = "built";
+pub const SPEND_BROADCAST: &str = "broadcast";
+pub const SPEND_FAILED: &str = "failed";
+pub const SPEND_REPLACED: &str = "replaced";
+
+/// A puzzle row.
+#[derive(Debug, Clone, Serialize)]
+pub struct PuzzleRecord {
+    pub id: i64,
+    pub target_hash: String,
+    pub address: String,
+    pub network: String,
+    pub contract: String,
+    pub amount_sats: Option<Amount>,
+    pub hint: Option<String>,
+    pub status: String,
+    pub created_at: Option<String>,
+    pub public_file: Option<String>,
+    pub secret_file: Option<String>,
+}
+
+impl PuzzleRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(PuzzleRecord {
+            id: row.get("id")?,
+            target_hash: row.get("target_hash")?,
+            address: row.get("address")?,
+            network: row.get("network")?,
+            contract: row.get("contract")?,
+            amount_sats: row.get("amount_sats")?,
+            hint: row.get("hint")?,
+            status: row.get("status")?,
+            created_at: row.get("created_at")?,
+            public_file: row.get("public_file")?,
+            secret_file: row.get("secret_file")?,
+        })
+    }
+}
+
+/// Fields needed to insert a puzzle.
+#[derive(Debug, Clone)]
+pub struct NewPuzzle {
+    pub target_hash: String,
+    pub address: String,
+    pub network: String,
+    pub contract: String,
+    pub amount_sats: Option<Amount>,
+    pub hint: Option<String>,
+    pub created_at: Option<String>,
+    pub public_file: Option<String>,
+    pub secret_file: Option<String>,
+}
+
+/// A funding outpoint row.
+#[derive(Debug, Clone, Serialize)]
+pub struct FundingOutpoint {
+    pub txid: String,
+    pub vout: u32,
+    pub amount_sats: Option<Amount>,
+    pub asset: Option<String>,
+    pub spent_by: Option<String>,
+}
+
+/// A signed spend of a puzzle outpoint made by this machine, kept so it
+/// can be rebroadcast or fee-bumped.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendRecord {
+    pub funding_txid: String,
+    pub funding_vout: u32,
+    pub txid: String,
+    pub tx_hex: String,
+    pub fee: Amount,
+    pub vsize: usize,
+    pub destination: Option<String>,
+    pub status: String,
+    /// The spend this one replaced, for fee bumps
+    pub replaces: Option<String>,
+    pub created_at: String,
+}
+
+impl SpendRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(SpendRecord {
+            funding_txid: row.get("funding_txid")?,
+            funding_vout: row.get("funding_vout")?,
+            txid: row.get("txid")?,
+            tx_hex: row.get("tx_hex")?,
+            fee: Amount::from_sat(row.get::<_, i64>("fee_sats")? as u64),
+            vsize: row.get::<_, i64>("vsize")? as usize,
+            destination: row.get("destination")?,
+            status: row.get("status")?,
+            replaces: row.get("replaces")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+/// A status history row.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusChange {
+    pub status: String,
+    pub note: Option<String>,
+    pub recorded_at: String,
+}
+
+/// A participant's registered nickname (see registration.rs).
+#[derive(Debug, Clone, Serialize)]
+pub struct Participant {
+    /// Unconfidential address the participant solves to
+    pub address: String,
+    pub nickname: String,
+    pub signature: String,
+    /// Unix time in the signed message; 0 for registrations made before
+    /// it was signed
+    pub signed_at: u64,
+    pub registered_at: String,
+}
+
+impl Participant {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Participant {
+            address: row.get(0)?,
+            nickname: row.get(1)?,
+            signature: row.get(2)?,
+            signed_at: row.get(3)?,
+            registered_at: row.get(4)?,
+        })
+    }
+}
+
+/// Outcome of importing one JSON file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Inserted,
+    AlreadyPresent,
+}
+
+pub struct PuzzleStore {
+    conn: Connection,
+}
+
+impl PuzzleStore {
+    /// Database path from PUZZLE_DB, or puzzles.db.
+    pub fn default_path() -> PathBuf {
+        std::env::var(DB_PATH_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_DB_PATH))
+    }
+
+    /// Open the database at the default path.
+    pub fn open_default() -> Result<Self> {
+        Self::open(&Self::default_path())
+    }
+
+    /// Open (creating if needed) the database at `path` and migrate it.
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open puzzle database {}", path.display()))?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        let store = PuzzleStore { conn };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    fn migrate(&self) -> Result<()> {
+        let version: usize = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))?
+            as usize;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            self.conn
+                .execute_batch(migration)
+                .with_context(|| format!("Failed to apply database migration {}", index + 1))?;
+            self.conn.pragma_update(None, "user_version", (index + 1) as i64)?;
+        }
+
+        Ok(())
+    }
+
+    /// Insert a puzzle, or return the id of the existing one with the same hash.
+    pub fn insert_puzzle(&self, puzzle: &NewPuzzle) -> Result<(i64, ImportOutcome)> {
+        if let Some(existing) = self.find_by_hash(&puzzle.target_hash)? {
+            return Ok((existing.id, ImportOutcome::AlreadyPresent));
+        }
+
+        self.conn.execute(
+            "INSERT INTO puzzles (target_hash, address, network, contract, amount_sats, hint,
+                                  created_at, public_file, secret_file)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                puzzle.target_hash,
+                puzzle.address,
+                puzzle.network,
+                puzzle.contract,
+                puzzle.amount_sats,
+                puzzle.hint,
+                puzzle.created_at,
+                puzzle.public_file,
+                puzzle.secret_file,
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.set_status(id, STATUS_UNKNOWN, Some("added to database"))?;
+
+        Ok((id, ImportOutcome::Inserted))
+    }
+
+    /// Record an outpoint funding a puzzle. Duplicate outpoints are ignored.
+    pub fn add_funding_outpoint(
+        &self,
+        puzzle_id: i64,
+        txid: &str,
+        vout: u32,
+        amount: Option<Amount>,
+        asset: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO funding_outpoints (puzzle_id, txid, vout, amount_sats, asset)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![puzzle_id, txid, vout, amount, asset],
+        )?;
+        Ok(())
+    }
+
+    /// Record extra funds sent to a puzzle after creation.
+    pub fn add_pot_addition(&self, puzzle_id: i64, txid: &str, vout: u32, amount: Option<Amount>) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO pot_additions (puzzle_id, txid, vout, amount_sats, added_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![puzzle_id, txid, vout, amount, now()],
+        )?;
+        self.add_funding_outpoint(puzzle_id, txid, vout, amount, None)
+    }
+
+    /// Change a puzzle's status. A history row is written only when the
+    /// status actually changes.
+    pub fn set_status(&self, puzzle_id: i64, status: &str, note: Option<&str>) -> Result<()> {
+        let last: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT status FROM status_history WHERE puzzle_id = ?1 ORDER BY id DESC LIMIT 1",
+                params![puzzle_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if last.as_deref() == Some(status) {
+            return Ok(());
+        }
+
+        self.conn.execute(
+            "UPDATE puzzles SET status = ?1 WHERE id = ?2",
+            params![status, puzzle_id],
+        )?;
+        self.conn.execute(
+            "INSERT INTO status_history (puzzle_id, status, note, recorded_at) VALUES (?1, ?2, ?3, ?4)",
+            params![puzzle_id, status, note, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Record the transaction that spent the puzzle outpoint `txid:vout`.
+    /// The puzzle is marked solved once none of its outpoints is left
+    /// unspent. Recording the same transaction again updates its
+    /// confirmation.
+    pub fn record_solve(&self, puzzle_id: i64, txid: &str, vout: u32, solve: &SolveInfo) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO solve_transactions
+                 (puzzle_id, txid, destination, secret, secret_verified, block_height, block_time, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(puzzle_id, txid) DO UPDATE SET
+                 block_height = excluded.block_height,
+                 block_time = excluded.block_time",
+            params![
+                puzzle_id,
+                solve.txid,
+                solve.destination,
+                solve.secret_hex,
+                solve.verified,
+                solve.block_height.map(|h| h as i64),
+                solve.block_time,
+                now(),
+            ],
+        )?;
+        self.conn.execute(
+            "UPDATE funding_outpoints SET spent_by = ?1 WHERE puzzle_id = ?2 AND txid = ?3 AND vout = ?4",
+            params![solve.txid, puzzle_id, txid, vout],
+        )?;
+        let unspent: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM funding_outpoints WHERE puzzle_id = ?1 AND spent_by IS NULL",
+            params![puzzle_id],
+            |row| row.get(0),
+        )?;
+        if unspent == 0 {
+            self.set_status(puzzle_id, STATUS_SOLVED, Some(&solve.txid))?;
+        }
+        Ok(())
+    }
+
+    /// Height and hash of the last block searched for a spend of `txid:vout`.
+    pub fn scan_marker(&self, txid: &str, vout: u32) -> Result<Option<(u64, String)>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT scanned_height, scanned_block FROM funding_outpoints
+                 WHERE txid = ?1 AND vout = ?2 AND scanned_height IS NOT NULL",
+                params![txid, vout],
+                |row| Ok((row.get::<_, i64>(0)? as u64, row.get(1)?)),
+            )
+            .optional()?)
+    }
+
+    /// Remember that no block up to `height` spends `txid:vout`.
+    pub fn set_scan_marker(&self, txid: &str, vout: u32, height: u64, block: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE funding_outpoints SET scanned_height = ?1, scanned_block = ?2 WHERE txid = ?3 AND vout = ?4",
+            params![height as i64, block, txid, vout],
+        )?;
+        Ok(())
+    }
+
+    /// Forget a solve that left the chain (reorged out or evicted from
+    /// the mempool): its row goes and the outpoints it spent are unspent
+    /// again.
+    pub fn remove_solve(&self, txid: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM solve_transactions WHERE txid = ?1", params![txid])?;
+        self.conn.execute("UPDATE funding_outpoints SET spent_by = NULL WHERE spent_by = ?1", params![txid])?;
+        Ok(())
+    }
+
+    /// The recorded solve of a puzzle, if any.
+    pub fn solve_for(&self, puzzle_id: i64) -> Result<Option<SolveInfo>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT s.txid, s.destination, s.secret, s.secret_verified, s.block_height, s.block_time, p.nickname
+                 FROM solve_transactions s LEFT JOIN participants p ON p.address = s.destination
+                 WHERE s.puzzle_id = ?1 ORDER BY s.id DESC LIMIT 1",
+                params![puzzle_id],
+                |row| {
+                    let secret_hex: Option<String> = row.get(2)?;
+                    let secret_text = secret_hex
+                        .as_deref()
+                        .and_then(|h| hex::decode(h).ok())
+                        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                        .and_then(|bytes| solves::secret_as_text(&bytes));
+                    Ok(SolveInfo {
+                        txid: row.get(0)?,
+                        destination: row.get(1)?,
+                        secret_hex,
+                        secret_text,
+                        verified: row.get(3)?,
+                        block_height: row.get::<_, Option<i64>>(4)?.map(|h| h as u64),
+                        block_time: row.get(5)?,
+                        winner: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Record a watcher event. `payload` is the full event as JSON.
+    pub fn record_event(
+        &self,
+        puzzle_id: i64,
+        kind: &str,
+        txid: Option<&str>,
+        vout: Option<u32>,
+        block_height: Option<u64>,
+        payload: &serde_json::Value,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO events (puzzle_id, kind, txid, vout, block_height, payload, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                puzzle_id,
+                kind,
+                txid,
+                vout,
+                block_height.map(|h| h as i64),
+                payload.to_string(),
+                now(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent events of a puzzle as stored JSON payloads, oldest first.
+    pub fn events_for(&self, puzzle_id: i64, limit: usize) -> Result<Vec<serde_json::Value>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT payload FROM (
+                 SELECT id, payload FROM events WHERE puzzle_id = ?1 ORDER BY id DESC LIMIT ?2
+             ) ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![puzzle_id, limit as i64], |row| row.get::<_, String>(0))?;
+        rows.map(|payload| Ok(serde_json::from_str(&payload?)?)).collect()
+    }
+
+    /// Save a signed spend. `record.created_at` is ignored.
+    pub fn record_spend(&self, record: &SpendRecord) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO spends
+                 (funding_txid, funding_vout, txid, tx_hex, fee_sats, vsize, destination, status, replaces, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(txid) DO UPDATE SET status = excluded.status",
+            params![
+                record.funding_txid,
+                record.funding_vout,
+                record.txid,
+                record.tx_hex,
+                record.fee.to_sat() as i64,
+                record.vsize as i64,
+                record.destination,
+                record.status,
+                record.replaces,
+                now(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_spend_status(&self, txid: &str, status: &str) -> Result<()> {
+        self.conn.execute("UPDATE spends SET status = ?1 WHERE txid = ?2", params![status, txid])?;
+        Ok(())
+    }
+
+    pub fn spend(&self, txid: &str) -> Result<Option<SpendRecord>> {
+        Ok(self
+            .conn
+            .query_row("SELECT * FROM spends WHERE txid = ?1", params![txid], SpendRecord::from_row)
+            .optional()?)
+    }
+
+    /// Our latest broadcast spend of `funding_txid:vout`, if any.
+    pub fn latest_spend(&self, funding_txid: &str, vout: u32) -> Result<Option<SpendRecord>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT * FROM spends WHERE funding_txid = ?1 AND funding_vout = ?2 AND status = ?3
+                 ORDER BY id DESC LIMIT 1",
+                params![funding_txid, vout, SPEND_BROADCAST],
+                SpendRecord::from_row,
+            )
+            .optional()?)
+    }
+
+    /// Whether `txid` is one of our spends of `funding_txid:vout`, in any state.
+    pub fn is_own_spend(&self, funding_txid: &str, vout: u32, txid: &str) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM spends WHERE funding_txid = ?1 AND funding_vout = ?2 AND txid = ?3",
+            params![funding_txid, vout, txid],
+            |row| row.get::<_, i64>(0),
+        )? > 0)
+    }
+
+    /// Register or change the nickname of an address. The signature must
+    /// have been checked; a nickname taken by another address, or a
+    /// registration signed no later than the recorded one, is refused.
+    pub fn register_participant(&self, address: &str, nickname: &str, signature: &str, signed_at: u64) -> Result<()> {
+        if let Some(owner) = self.participant_by_nickname(nickname)? {
+            if owner.address != address {
+                return Err(anyhow::anyhow!("Nickname {} is already registered to {}", owner.nickname, owner.address));
+            }
+        }
+        let changed = self.conn.execute(
+            "INSERT INTO participants (address, nickname, signature, signed_at, registered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(address) DO UPDATE SET
+                 nickname = excluded.nickname,
+                 signature = excluded.signature,
+                 signed_at = excluded.signed_at,
+                 registered_at = excluded.registered_at
+             WHERE excluded.signed_at > participants.signed_at",
+            params![address, nickname, signature, signed_at as i64, now()],
+        )?;
+        if changed == 0 {
+            return Err(anyhow::anyhow!("A registration of {} signed at or after {} is already recorded", address, signed_at));
+        }
+        Ok(())
+    }
+
+    /// The registration of an address, if any.
+    pub fn participant(&self, address: &str) -> Result<Option<Participant>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT address, nickname, signature, signed_at, registered_at FROM participants WHERE address = ?1",
+                params![address],
+                Participant::from_row,
+            )
+            .optional()?)
+    }
+
+    /// The registered nickname of an address, if any.
+    pub fn nickname(&self, address: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT nickname FROM participants WHERE address = ?1", params![address], |row| row.get(0))
+            .optional()?)
+    }
+
+    /// Fill in the nickname of a solve's winner, if they registered.
+    pub fn name_winner(&self, solve: &mut SolveInfo) -> Result<()> {
+        if let Some(destination) = &solve.destination {
+            solve.winner = self.nickname(destination)?;
+        }
+        Ok(())
+    }
+
+    pub fn participant_by_nickname(&self, nickname: &str) -> Result<Option<Participant>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT address, nickname, signature, signed_at, registered_at FROM participants WHERE nickname = ?1",
+                params![nickname],
+                Participant::from_row,
+            )
+            .optional()?)
+    }
+
+    pub fn participants(&self) -> Result<Vec<Participant>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT address, nickname, signature, signed_at, registered_at FROM participants ORDER BY nickname")?;
+        let rows = stmt.query_map([], Participant::from_row)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Update where a puzzle's files live, e.g. after archiving.
+    pub fn set_files(&self, puzzle_id: i64, public_file: Option<&str>, secret_file: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE puzzles SET public_file = ?1, secret_file = ?2 WHERE id = ?3",
+            params![public_file, secret_file, puzzle_id],
+        )?;
+        Ok(())
+    }
+
+    /// All puzzles, oldest first.
+    pub fn list_puzzles(&self) -> Result<Vec<PuzzleRecord>> {
+        let mut stmt = self.conn.prepare("SELECT * FROM puzzles ORDER BY id")?;
+        let rows = stmt.query_map([], PuzzleRecord::from_row)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Look a puzzle up by its full target hash.
+    pub fn find_by_hash(&self, target_hash: &str) -> Result<Option<PuzzleRecord>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT * FROM puzzles WHERE target_hash = ?1",
+                params![target_hash],
+                PuzzleRecord::from_row,
+            )
+            .optional()?)
+    }
+
+    /// Look a puzzle up by a hash prefix, with or without "0x".
+    /// Fails if the prefix is ambiguous.
+    pub fn find_by_prefix(&self, prefix: &str) -> Result<Option<PuzzleRecord>> {
+        let prefix = prefix.trim_start_matches("0x").to_lowercase();
+        let pattern = format!("0x{}%", prefix);
+
+        let mut stmt = self.conn.prepare("SELECT * FROM puzzles WHERE target_hash LIKE ?1")?;
+        let mut matches = stmt
+            .query_map(params![pattern], PuzzleRecord::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        if matches.len() > 1 {
+            return Err(anyhow::anyhow!(
+                "Hash prefix {} matches {} puzzles, use a longer prefix",
+                prefix,
+                matches.len()
+            ));
+        }
+        Ok(matches.pop())
+    }
+
+    /// Funding outpoints of a puzzle.
+    pub fn funding_outpoints(&self, puzzle_id: i64) -> Result<Vec<FundingOutpoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT txid, vout, amount_sats, asset, spent_by FROM funding_outpoints
+             WHERE puzzle_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![puzzle_id], |row| {
+            Ok(FundingOutpoint {
+                txid: row.get(0)?,
+                vout: row.get(1)?,
+                amount_sats: row.get(2)?,
+                asset: row.get(3)?,
+                spent_by: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Status history of a puzzle, oldest first.
+    pub fn status_history(&self, puzzle_id: i64) -> Result<Vec<StatusChange>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT status, note, recorded_at FROM status_history WHERE puzzle_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![puzzle_id], |row| {
+            Ok(StatusChange {
+                status: row.get(0)?,
+                note: row.get(1)?,
+                recorded_at: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Import a public puzzle_XXXX.json file, picking up the matching
+    /// SECRET file path if it sits next to it.
+    pub fn import_json_file(&self, path: &Path) -> Result<ImportOutcome> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let puzzle: serde_json::Value = serde_json::from_str(&data)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let target_hash = puzzle["target_hash"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing target_hash in {}", path.display()))?;
+        let address = puzzle["address"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing address in {}", path.display()))?;
+
+        let secret_path = secret_path_for(path);
+        let new_puzzle = NewPuzzle {
+            target_hash: target_hash.to_string(),
+            address: address.to_string(),
+            network: puzzle["network"].as_str().unwrap_or("liquidtestnet").to_string(),
+            contract: puzzle["contract"].as_str().unwrap_or("puzzle_jackpot").to_string(),
+            amount_sats: json_amount(&puzzle["amount"]),
+            hint: puzzle["hint"].as_str().map(str::to_string),
+            created_at: puzzle["created_at"].as_str().map(str::to_string),
+            public_file: Some(path.display().to_string()),
+            secret_file: secret_path
+                .filter(|p| p.exists())
+                .map(|p| p.display().to_string()),
+        };
+
+        let (id, outcome) = self.insert_puzzle(&new_puzzle)?;
+
+        if let Some(txid) = puzzle["txid"].as_str() {
+            let vout = puzzle["vout"].as_u64().unwrap_or(0) as u32;
+            self.add_funding_outpoint(
+                id,
+                txid,
+                vout,
+                new_puzzle.amount_sats,
+                puzzle["asset"].as_str(),
+            )?;
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// puzzle_XXXX.json -> puzzle_XXXX_SECRET.json
+fn secret_path_for(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+    Some(path.with_file_name(format!("{}_SECRET.json", stem)))
+}
+
+/// The "amount" field is a string in files written by create-puzzle,
+/// but may be a number in hand-written files. The number is read from
+/// its JSON text, so 0.1 stays exactly 10000000 sats.
+fn json_amount(value: &serde_json::Value) -> Option<Amount> {
+    match value {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => Amount::from_btc_str(&n.to_string()).ok().or_else(|| Amount::from_rpc(value)),
+        _ => None,
+    }
+}
+
+impl rusqlite::ToSql for Amount {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        let sats = i64::try_from(self.to_sat()).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        Ok(sats.into())
+    }
+}
+
+impl rusqlite::types::FromSql for Amount {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        let sats = value.as_i64()?;
+        u64::try_from(sats)
+            .map(Amount::from_sat)
+            .map_err(|_| rusqlite::types::FromSqlError::OutOfRange(sats))
+    }
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory database with only the first `count` migrations applied.
+    fn store_at(count: usize) -> PuzzleStore {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..count] {
+            conn.execute_batch(migration).unwrap();
+        }
+        conn.pragma_update(None, "user_version", count as i64).unwrap();
+        PuzzleStore { conn }
+    }
+
+    #[test]
+    fn migrates_a_new_database_once() {
+        let dir = std::env::temp_dir().join(format!("puzzle-hunt-store-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("puzzles.db");
+        let _ = std::fs::remove_file(&path);
+
+        let store = PuzzleStore::open(&path).unwrap();
+        let version: i64 = store.conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+        assert_eq!(version as usize, MIGRATIONS.len());
+        store.conn.execute("INSERT INTO puzzles (target_hash, address) VALUES ('aa', 'tex1a')", []).unwrap();
+        drop(store);
+
+        // Reopening applies nothing again and keeps the rows
+        let store = PuzzleStore::open(&path).unwrap();
+        assert_eq!(store.find_by_hash("aa").unwrap().unwrap().address, "tex1a");
+    }
+
+    #[test]
+    fn migrates_text_amounts_to_satoshis() {
+        let store = store_at(3);
+        store
+            .conn
+            .execute_batch(
+                "INSERT INTO puzzles (target_hash, address, amount) VALUES ('aa', 'tex1a', '0.10000000');
+                 INSERT INTO puzzles (target_hash, address, amount) VALUES ('bb', 'tex1b', '2');
+                 INSERT INTO puzzles (target_hash, address, amount) VALUES ('cc', 'tex1c', 'lots');
+                 INSERT INTO funding_outpoints (puzzle_id, txid, vout, amount) VALUES (1, 'tx', 0, '0.00050000');",
+            )
+            .unwrap();
+        store.migrate().unwrap();
+
+        let amounts: Vec<_> = store.list_puzzles().unwrap().into_iter().map(|p| p.amount_sats).collect();
+        assert_eq!(amounts, [Some(Amount::from_sat(10_000_000)), Some(Amount::from_sat(200_000_000)), None]);
+        assert_eq!(store.funding_outpoints(1).unwrap()[0].amount_sats, Some(Amount::from_sat(50_000)));
+    }
+
+    fn add_puzzle(store: &PuzzleStore, target_hash: &str, funding_txid: &str) -> i64 {
+        let (id, _) = store
+            .insert_puzzle(&NewPuzzle {
+                target_hash: target_hash.to_string(),
+                address: format!("tex1{}", target_hash),
+                network: "liquidtestnet".to_string(),
+                contract: "puzzle_jackpot".to_string(),
+                amount_sats: Some(Amount::from_sat(50_000)),
+                hint: None,
+                created_at: None,
+                public_file: None,
+                secret_file: None,
+            })
+            .unwrap();
+        store.add_funding_outpoint(id, funding_txid, 0, Some(Amount::from_sat(50_000)), None).unwrap();
+        id
+    }
+
+    fn solve(txid: &str, block_height: Option<u64>) -> SolveInfo {
+        SolveInfo {
+            txid: txid.to_string(),
+            secret_hex: None,
+            secret_text: None,
+            verified: false,
+            destination: Some("tex1winner".to_string()),
+            block_height,
+            block_time: None,
+            winner: None,
+        }
+    }
+
+    #[test]
+    fn keeps_a_solve_row_for_each_puzzle_of_one_transaction() {
+        let store = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        let first = add_puzzle(&store, "aa", "f1");
+        let second = add_puzzle(&store, "bb", "f2");
+
+        store.record_solve(first, "f1", 0, &solve("spend", None)).unwrap();
+        store.record_solve(second, "f2", 0, &solve("spend", None)).unwrap();
+        // Seeing it again in a block only updates the confirmation
+        store.record_solve(first, "f1", 0, &solve("spend", Some(100))).unwrap();
+
+        assert_eq!(store.solve_for(first).unwrap().unwrap().block_height, Some(100));
+        assert_eq!(store.solve_for(second).unwrap().unwrap().txid, "spend");
+        assert_eq!(store.find_by_hash("bb").unwrap().unwrap().status, STATUS_SOLVED);
+    }
+
+    #[test]
+    fn saves_where_a_solve_scan_stopped() {
+        let store = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        add_puzzle(&store, "aa", "f1");
+
+        assert_eq!(store.scan_marker("f1", 0).unwrap(), None);
+        store.set_scan_marker("f1", 0, 120, "blockhash").unwrap();
+        assert_eq!(store.scan_marker("f1", 0).unwrap(), Some((120, "blockhash".to_string())));
+    }
+
+    #[test]
+    fn records_a_spend_again_as_a_status_change() {
+        let store = PuzzleStore::open(Path::new(":memory:")).unwrap();
+        let mut spend = SpendRecord {
+            funding_txid: "f1".to_string(),
+            funding_vout: 0,
+            txid: "spend".to_string(),
+            tx_hex: "00".to_string(),
+            fee: Amount::from_sat(300),
+            vsize: 150,
+            destination: None,
+            status: SPEND_BUILT.to_string(),
+            replaces: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        store.record_spend(&spend).unwrap();
+        spend.status = SPEND_BROADCAST.to_string();
+        store.record_spend(&spend).unwrap();
+
+        assert_eq!(store.spend("spend").unwrap().unwrap().status, SPEND_BROADCAST);
+        assert_eq!(store.latest_spend("f1", 0).unwrap().unwrap().txid, "spend");
+    }
+}
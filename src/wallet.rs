@@ -0,0 +1,178 @@
+/*
+ * WALLET - Pay a Simplicity spend's fee from the node wallet
+ *
+ * By default a puzzle spend pays its fee out of the prize. With a wallet
+ * fee input the prize goes out whole:
+ *   1. pick a wallet UTXO of the fee asset large enough for the fee
+ *   2. add it as an extra input, plus a blinded change output back to
+ *      the wallet and an explicit fee output
+ *   3. let walletprocesspsbt blind the change and sign the wallet input
+ *   4. put the Simplicity witness back on the contract input
+ *
+ * The contract input needs no signature, and the wallet's SIGHASH_ALL
+ * signature does not cover witnesses, so the Simplicity witness can be
+ * attached after signing. The wallet only blinds its own change, so the
+ * contract input and the other outputs must be explicit.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use anyhow::{Context, Result};
+use base64::Engine;
+use elements::pset::PartiallySignedTransaction as Psbt;
+use elements::{confidential, Address, AssetId, OutPoint, Transaction, TxIn, TxInWitness, TxOut};
+use std::str::FromStr;
+
+/// Change below this is not worth an output; such UTXOs are skipped.
+pub const MIN_CHANGE: Amount = Amount::from_sat(1_000);
+
+/// A wallet UTXO chosen to pay a fee.
+#[derive(Debug, Clone)]
+pub struct FeeInput {
+    pub outpoint: OutPoint,
+    pub utxo: TxOut,
+    pub amount: Amount,
+}
+
+/// The smallest confirmed wallet UTXO of `asset` covering `fee` plus change.
+pub fn select_fee_input(cli: &ElementsCli, asset: AssetId, fee: Amount) -> Result<FeeInput> {
+    let query = serde_json::json!({ "asset": asset.to_string() });
+    let unspent = cli.call_wallet_json(&["listunspent", "1", "9999999", "[]", "false", &query.to_string()])?;
+
+    let needed = fee.checked_add(MIN_CHANGE).context("Fee too large")?;
+    let best = unspent
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|utxo| utxo["spendable"].as_bool() != Some(false))
+        .filter_map(|utxo| Some((utxo, Amount::from_rpc(&utxo["amount"])?)))
+        .filter(|(_, amount)| *amount >= needed)
+        .min_by_key(|(_, amount)| *amount);
+
+    let Some((utxo, amount)) = best else {
+        return Err(anyhow::anyhow!(
+            "❌ Wallet '{}' has no confirmed UTXO of {} with at least {} sats",
+            cli.wallet(),
+            asset,
+            needed.to_sat()
+        ));
+    };
+
+    let txid = utxo["txid"].as_str().context("listunspent entry without txid")?;
+    let vout = utxo["vout"].as_u64().context("listunspent entry without vout")? as u32;
+
+    // The full output, with commitments and proofs, from the wallet transaction
+    let wallet_tx = cli.call_wallet_json(&["gettransaction", txid])?;
+    let tx_hex = wallet_tx["hex"].as_str().context("gettransaction returned no hex")?;
+    let tx: Transaction = elements::encode::deserialize(&hex::decode(tx_hex)?)?;
+    let txout = tx.output.get(vout as usize).cloned()
+        .ok_or_else(|| anyhow::anyhow!("Wallet transaction {} has no output {}", txid, vout))?;
+
+    Ok(FeeInput {
+        outpoint: OutPoint::new(elements::Txid::from_str(txid)?, vout),
+        utxo: txout,
+        amount,
+    })
+}
+
+/// `tx` with a placeholder wallet input and change output, for sizing.
+/// Confidential change costs the same discounted vbytes as explicit change.
+pub fn draft_with_fee_input(tx: &Transaction, asset: AssetId) -> Transaction {
+    let mut draft = tx.clone();
+    let mut input = plain_input(OutPoint::default());
+    // P2WPKH signature and public key
+    input.witness.script_witness = vec![vec![0; 72], vec![0; 33]];
+    draft.input.push(input);
+
+    let change = TxOut {
+        value: confidential::Value::Explicit(0),
+        script_pubkey: elements::Script::from(vec![0; 22]),
+        asset: confidential::Asset::Explicit(asset),
+        nonce: confidential::Nonce::Null,
+        witness: elements::TxOutWitness::empty(),
+    };
+    let fee_position = draft.output.iter().position(TxOut::is_fee).unwrap_or(draft.output.len());
+    draft.output.insert(fee_position, change);
+    draft
+}
+
+/// Add `fee_input` and a change output to `tx`, pay `fee` in `asset`,
+/// and have the wallet blind the change and sign its input.
+///
+/// `tx` spends only the contract input (index 0, finalized witness
+/// attached) and must not have a fee output yet. `contract_utxo` is the
+/// output it spends.
+pub fn pay_fee_from_wallet(
+    cli: &ElementsCli,
+    tx: &Transaction,
+    contract_utxo: TxOut,
+    fee_input: &FeeInput,
+    fee: Amount,
+    asset: AssetId,
+) -> Result<Transaction> {
+    let contract_witness = tx.input[0].witness.script_witness.clone();
+
+    let change_value = fee_input.amount.checked_sub(fee).context("Fee input does not cover the fee")?;
+    let change_address = Address::from_str(&cli.call_wallet(&["getrawchangeaddress"])?)
+        .context("Wallet returned an invalid change address")?;
+    let change_blinding_key = change_address
+        .blinding_pubkey
+        .context("Wallet change address is not confidential")?;
+
+    let mut unsigned = tx.clone();
+    for input in unsigned.input.iter_mut() {
+        input.witness = TxInWitness::empty();
+    }
+    unsigned.input.push(plain_input(fee_input.outpoint));
+    unsigned.output.push(TxOut {
+        value: confidential::Value::Explicit(change_value.to_sat()),
+        script_pubkey: change_address.script_pubkey(),
+        asset: confidential::Asset::Explicit(asset),
+        nonce: confidential::Nonce::Null,
+        witness: elements::TxOutWitness::empty(),
+    });
+    unsigned.output.push(TxOut::new_fee(fee.to_sat(), asset));
+    let change_index = unsigned.output.len() - 2;
+    let wallet_index = unsigned.input.len() - 1;
+
+    let mut pset = Psbt::from_tx(unsigned);
+    pset.inputs_mut()[0].witness_utxo = Some(contract_utxo);
+    pset.inputs_mut()[wallet_index].witness_utxo = Some(fee_input.utxo.clone());
+    let change = &mut pset.outputs_mut()[change_index];
+    change.blinding_key = Some(elements::bitcoin::PublicKey::new(change_blinding_key));
+    change.blinder_index = Some(wallet_index as u32);
+
+    let processed = cli.call_wallet_json(&["walletprocesspsbt", &encode_pset(&pset)])?;
+    let processed = processed["psbt"].as_str().context("walletprocesspsbt returned no psbt")?;
+    let mut pset = decode_pset(processed)?;
+
+    if pset.inputs()[wallet_index].final_script_witness.is_none() {
+        return Err(anyhow::anyhow!("❌ Wallet did not sign the fee input {}", fee_input.outpoint));
+    }
+    pset.inputs_mut()[0].final_script_witness = Some(contract_witness);
+
+    pset.extract_tx()
+        .map_err(|e| anyhow::anyhow!("Failed to extract transaction: {:?}", e))
+}
+
+pub fn encode_pset(pset: &Psbt) -> String {
+    base64::engine::general_purpose::STANDARD.encode(elements::encode::serialize(pset))
+}
+
+pub fn decode_pset(encoded: &str) -> Result<Psbt> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .context("PSET is not valid base64")?;
+    elements::encode::deserialize(&bytes).context("Failed to decode PSET")
+}
+
+fn plain_input(outpoint: OutPoint) -> TxIn {
+    TxIn {
+        previous_output: outpoint,
+        is_pegin: false,
+        script_sig: elements::Script::new(),
+        sequence: elements::Sequence::ENABLE_RBF_NO_LOCKTIME,
+        asset_issuance: elements::AssetIssuance::null(),
+        witness: TxInWitness::empty(),
+    }
+}
@@ -0,0 +1,582 @@
+/*
+ * WATCHER - Track puzzle outpoints and report lifecycle events
+ *
+ * Each poll compares the node's view of every known puzzle with what
+ * the watcher saw last time and emits an Event for each change:
+ *
+ *   funded           first output paying a puzzle address
+ *   pot_increased    another output paying the same address
+ *   confirmed        a funding output got its first confirmation
+ *   solve_in_mempool a transaction spending the puzzle is in the mempool,
+ *                    or replaced (RBF) the one seen before
+ *   solve_confirmed  that transaction is in a block
+ *   reorged          a confirmation (or a mempool solve) disappeared
+ *
+ * hint_released events come from hints.rs, not from polling.
+ *
+ * Events are written to the puzzle database and handed to sinks:
+ * stdout JSON lines, an HTTP webhook, or a shell hook.
+ */
+
+use crate::amount::Amount;
+use crate::chain::ElementsCli;
+use crate::solves::{self, SolveInfo};
+use crate::store::{self, FundingOutpoint, PuzzleRecord, PuzzleStore};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Funded,
+    PotIncreased,
+    Confirmed,
+    SolveInMempool,
+    SolveConfirmed,
+    Reorged,
+    HintReleased,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Funded => "funded",
+            EventKind::PotIncreased => "pot_increased",
+            EventKind::Confirmed => "confirmed",
+            EventKind::SolveInMempool => "solve_in_mempool",
+            EventKind::SolveConfirmed => "solve_confirmed",
+            EventKind::Reorged => "reorged",
+            EventKind::HintReleased => "hint_released",
+        }
+    }
+}
+
+/// A puzzle lifecycle event. `txid`/`vout` always name the puzzle
+/// outpoint; the spending transaction is in `solve`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub kind: EventKind,
+    pub target_hash: String,
+    pub address: String,
+    pub txid: String,
+    pub vout: u32,
+    pub amount_sats: Option<Amount>,
+    pub block_height: Option<u64>,
+    pub solve: Option<SolveInfo>,
+    pub detail: Option<String>,
+    pub time: String,
+}
+
+/// Somewhere to send events.
+pub trait EventSink {
+    fn emit(&mut self, event: &Event) -> Result<()>;
+}
+
+/// One JSON object per line on stdout.
+pub struct StdoutSink;
+
+impl EventSink for StdoutSink {
+    fn emit(&mut self, event: &Event) -> Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", serde_json::to_string(event)?)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// How long a webhook may take to connect, accept a request or answer.
+/// A stuck endpoint must not stall the watcher.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// POST each event as JSON to a local http:// endpoint.
+pub struct WebhookSink {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Result<Self> {
+        let rest = url.strip_prefix("http://")
+            .ok_or_else(|| anyhow::anyhow!("Webhook URL must start with http:// (got {})", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().context("Invalid webhook port")?),
+            None => (authority, 80),
+        };
+
+        Ok(WebhookSink {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+impl WebhookSink {
+    /// Connect to the first address of the webhook host that answers.
+    fn connect(&self) -> Result<TcpStream> {
+        let mut last_error = None;
+        for address in (self.host.as_str(), self.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, WEBHOOK_TIMEOUT) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(WEBHOOK_TIMEOUT))?;
+                    stream.set_write_timeout(Some(WEBHOOK_TIMEOUT))?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(match last_error {
+            Some(e) => anyhow::Error::from(e),
+            None => anyhow::anyhow!("{} has no address", self.host),
+        })
+        .with_context(|| format!("Failed to connect to webhook {}:{}", self.host, self.port))
+    }
+}
+
+impl EventSink for WebhookSink {
+    fn emit(&mut self, event: &Event) -> Result<()> {
+        let body = serde_json::to_string(event)?;
+        let mut stream = self.connect()?;
+
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            body.len(),
+            body
+        )?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        let status = response.split_whitespace().nth(1).unwrap_or("");
+        if !status.starts_with('2') {
+            return Err(anyhow::anyhow!("Webhook answered {}", status));
+        }
+        Ok(())
+    }
+}
+
+/// Run a shell command per event, with the event JSON on stdin and
+/// PUZZLE_EVENT / PUZZLE_TARGET_HASH / PUZZLE_TXID in the environment.
+pub struct HookSink {
+    command: String,
+}
+
+impl HookSink {
+    pub fn new(command: &str) -> Self {
+        HookSink { command: command.to_string() }
+    }
+}
+
+impl EventSink for HookSink {
+    fn emit(&mut self, event: &Event) -> Result<()> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("PUZZLE_EVENT", event.kind.as_str())
+            .env("PUZZLE_TARGET_HASH", &event.target_hash)
+            .env("PUZZLE_TXID", &event.txid)
+            .stdin(Stdio::piped())
+            .spawn()
+            .context("Failed to run hook")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(serde_json::to_string(event)?.as_bytes())?;
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(anyhow::anyhow!("Hook exited with {}", status));
+        }
+        Ok(())
+    }
+}
+
+/// What the watcher knows about one puzzle outpoint.
+#[derive(Default)]
+struct OutpointState {
+    /// Block the outpoint was confirmed in
+    confirmed_in: Option<String>,
+    /// Transaction spending it, once seen
+    spent_by: Option<String>,
+    /// Block the spend was confirmed in
+    spend_confirmed_in: Option<String>,
+}
+
+pub struct Watcher {
+    cli: ElementsCli,
+    db: PuzzleStore,
+    outpoints: HashMap<(String, u32), OutpointState>,
+    seen_mempool: HashSet<String>,
+    last_tip: Option<String>,
+    initialized: bool,
+}
+
+impl Watcher {
+    pub fn new(cli: ElementsCli, db: PuzzleStore) -> Self {
+        Watcher {
+            cli,
+            db,
+            outpoints: HashMap::new(),
+            seen_mempool: HashSet::new(),
+            last_tip: None,
+            initialized: false,
+        }
+    }
+
+    /// The puzzle database, for callers reacting to events.
+    pub fn db(&self) -> &PuzzleStore {
+        &self.db
+    }
+
+    /// The node connection, for callers reacting to events.
+    pub fn cli(&self) -> &ElementsCli {
+        &self.cli
+    }
+
+    /// Check the node once and return what changed. The first poll
+    /// only learns the current state and reports nothing.
+    pub fn poll(&mut self) -> Result<Vec<Event>> {
+        let mut events = vec![];
+        let puzzles: Vec<PuzzleRecord> = self
+            .db
+            .list_puzzles()?
+            .into_iter()
+            .filter(|p| p.status != store::STATUS_ARCHIVED)
+            .collect();
+
+        let tip = self.cli.call(&["getbestblockhash"])?;
+        let new_tip = self.last_tip.as_deref() != Some(tip.as_str());
+
+        if new_tip && self.initialized {
+            self.check_reorgs(&puzzles, &mut events)?;
+        }
+
+        self.scan_mempool(&puzzles, &mut events)?;
+        if new_tip {
+            self.scan_utxo_set(&puzzles, &mut events)?;
+        }
+
+        for puzzle in &puzzles {
+            for outpoint in self.db.funding_outpoints(puzzle.id)? {
+                self.check_outpoint(puzzle, &outpoint, &mut events)?;
+            }
+        }
+
+        self.last_tip = Some(tip);
+        if !self.initialized {
+            self.initialized = true;
+            return Ok(vec![]);
+        }
+
+        for event in &events {
+            if let Some(puzzle) = puzzles.iter().find(|p| p.target_hash == event.target_hash) {
+                self.db.record_event(
+                    puzzle.id,
+                    event.kind.as_str(),
+                    Some(&event.txid),
+                    Some(event.vout),
+                    event.block_height,
+                    &serde_json::to_value(event)?,
+                )?;
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn in_mempool(&self, txid: &str) -> bool {
+        self.cli.call(&["getmempoolentry", txid]).is_ok()
+    }
+
+    fn event(&self, kind: EventKind, puzzle: &PuzzleRecord, txid: &str, vout: u32) -> Event {
+        Event {
+            kind,
+            target_hash: puzzle.target_hash.clone(),
+            address: puzzle.address.clone(),
+            txid: txid.to_string(),
+            vout,
+            amount_sats: None,
+            block_height: None,
+            solve: None,
+            detail: None,
+            time: chrono::Local::now().to_rfc3339(),
+        }
+    }
+
+    /// New outputs paying a puzzle address, seen in the mempool.
+    fn scan_mempool(&mut self, puzzles: &[PuzzleRecord], events: &mut Vec<Event>) -> Result<()> {
+        let mempool = self.cli.call_json(&["getrawmempool"])?;
+        let txids: Vec<String> = mempool
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t.as_str().map(str::to_string))
+            .collect();
+
+        // Forget transactions that left the mempool
+        self.seen_mempool.retain(|txid| txids.contains(txid));
+
+        for txid in txids {
+            if !self.seen_mempool.insert(txid.clone()) {
+                continue;
+            }
+            let Ok(tx) = self.cli.get_raw_transaction(&txid) else {
+                continue;
+            };
+            for output in tx["vout"].as_array().into_iter().flatten() {
+                let address = output["scriptPubKey"]["address"].as_str();
+                let Some(puzzle) = puzzles.iter().find(|p| Some(p.address.as_str()) == address) else {
+                    continue;
+                };
+                let vout = output["n"].as_u64().unwrap_or(0) as u32;
+                let amount = Amount::from_rpc(&output["value"]);
+                self.add_outpoint(puzzle, &txid, vout, amount, None, events)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Confirmed outputs paying a puzzle address that we have never seen,
+    /// e.g. sent while the watcher was not running.
+    fn scan_utxo_set(&mut self, puzzles: &[PuzzleRecord], events: &mut Vec<Event>) -> Result<()> {
+        if puzzles.is_empty() {
+            return Ok(());
+        }
+        let descriptors: Vec<String> = puzzles.iter().map(|p| format!("addr({})", p.address)).collect();
+        let scan = self
+            .cli
+            .call_json(&["scantxoutset", "start", &serde_json::to_string(&descriptors)?])?;
+
+        for utxo in scan["unspents"].as_array().into_iter().flatten() {
+            let desc = utxo["desc"].as_str().unwrap_or_default();
+            let Some(puzzle) = puzzles.iter().find(|p| desc.contains(&p.address)) else {
+                continue;
+            };
+            let txid = utxo["txid"].as_str().unwrap_or_default();
+            let vout = utxo["vout"].as_u64().unwrap_or(0) as u32;
+            let amount = Amount::from_rpc(&utxo["amount"]);
+            let height = utxo["height"].as_u64();
+            self.add_outpoint(puzzle, txid, vout, amount, height, events)?;
+        }
+        Ok(())
+    }
+
+    /// Record an outpoint paying a puzzle, if it is new.
+    fn add_outpoint(
+        &mut self,
+        puzzle: &PuzzleRecord,
+        txid: &str,
+        vout: u32,
+        amount: Option<Amount>,
+        block_height: Option<u64>,
+        events: &mut Vec<Event>,
+    ) -> Result<()> {
+        let known = self.db.funding_outpoints(puzzle.id)?;
+        if known.iter().any(|o| o.txid == txid && o.vout == vout) {
+            return Ok(());
+        }
+
+        let kind = if known.is_empty() {
+            self.db.add_funding_outpoint(puzzle.id, txid, vout, amount, None)?;
+            EventKind::Funded
+        } else {
+            self.db.add_pot_addition(puzzle.id, txid, vout, amount)?;
+            EventKind::PotIncreased
+        };
+
+        let mut event = self.event(kind, puzzle, txid, vout);
+        event.amount_sats = amount;
+        event.block_height = block_height;
+        events.push(event);
+        Ok(())
+    }
+
+    /// Compare one outpoint with what we saw last time.
+    fn check_outpoint(&mut self, puzzle: &PuzzleRecord, outpoint: &FundingOutpoint, events: &mut Vec<Event>) -> Result<()> {
+        let key = (outpoint.txid.clone(), outpoint.vout);
+        let is_new = !self.outpoints.contains_key(&key);
+        let mut state = self.outpoints.remove(&key).unwrap_or_default();
+        if is_new {
+            // A solve recorded before a restart is not searched for again
+            state.spent_by = outpoint.spent_by.clone();
+        }
+        let state = self.update_outpoint(
+            puzzle,
+            &outpoint.txid,
+            outpoint.vout,
+            outpoint.amount_sats,
+            state,
+            is_new,
+            events,
+        )?;
+        self.outpoints.insert(key, state);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn update_outpoint(
+        &mut self,
+        puzzle: &PuzzleRecord,
+        txid: &str,
+        vout: u32,
+        amount: Option<Amount>,
+        mut state: OutpointState,
+        is_new: bool,
+        events: &mut Vec<Event>,
+    ) -> Result<OutpointState> {
+        // A funding outpoint created outside the watcher (e.g. create-puzzle)
+        if is_new && self.initialized && state.confirmed_in.is_none() {
+            let already_reported = events.iter().any(|e| e.txid == txid && e.vout == vout);
+            if !already_reported {
+                let mut event = self.event(EventKind::Funded, puzzle, txid, vout);
+                event.amount_sats = amount;
+                events.push(event);
+            }
+        }
+
+        match self.cli.get_tx_out(txid, vout)? {
+            Some(utxo) => {
+                if let Some(spend) = state.spent_by.take() {
+                    // The spend we saw is gone: dropped from the mempool or reorged out
+                    let mut event = self.event(EventKind::Reorged, puzzle, txid, vout);
+                    event.detail = Some(format!("spend {} disappeared", spend));
+                    events.push(event);
+                    state.spend_confirmed_in = None;
+                    self.db.remove_solve(&spend)?;
+                    self.db.set_status(puzzle.id, store::STATUS_ACTIVE, Some("solve disappeared"))?;
+                }
+
+                let confirmations = utxo["confirmations"].as_u64().unwrap_or(0);
+                if confirmations > 0 && state.confirmed_in.is_none() {
+                    let tip: u64 = self.cli.call(&["getblockcount"])?.parse()?;
+                    let height = tip + 1 - confirmations;
+                    state.confirmed_in = Some(self.cli.call(&["getblockhash", &height.to_string()])?);
+
+                    let mut event = self.event(EventKind::Confirmed, puzzle, txid, vout);
+                    event.amount_sats = amount;
+                    event.block_height = Some(height);
+                    events.push(event);
+                    self.db.set_status(puzzle.id, store::STATUS_ACTIVE, Some("funding confirmed"))?;
+                }
+            }
+            None if state.spent_by.is_none() => {
+                if let Some(mut solve) = solves::find_solve(&self.cli, &self.db, txid, vout, &puzzle.target_hash)? {
+                    self.db.record_solve(puzzle.id, txid, vout, &solve)?;
+                    self.db.name_winner(&mut solve)?;
+                    state.spent_by = Some(solve.txid.clone());
+
+                    let kind = match solve.block_height {
+                        Some(height) => {
+                            state.spend_confirmed_in = Some(self.cli.call(&["getblockhash", &height.to_string()])?);
+                            EventKind::SolveConfirmed
+                        }
+                        None => EventKind::SolveInMempool,
+                    };
+                    let mut event = self.event(kind, puzzle, txid, vout);
+                    event.block_height = solve.block_height;
+                    event.solve = Some(solve);
+                    events.push(event);
+                }
+            }
+            None => {
+                if state.spend_confirmed_in.is_none() {
+                    let spend_txid = state.spent_by.clone().unwrap_or_default();
+                    let tx = match self.cli.get_raw_transaction(&spend_txid) {
+                        Ok(tx) if tx["blockhash"].is_string() || self.in_mempool(&spend_txid) => tx,
+                        // Gone from the mempool without confirming: replaced (RBF) by another spend
+                        _ => {
+                            if let Some(mut solve) = solves::find_solve(&self.cli, &self.db, txid, vout, &puzzle.target_hash)? {
+                                if solve.txid != spend_txid {
+                                    self.db.remove_solve(&spend_txid)?;
+                                    self.db.record_solve(puzzle.id, txid, vout, &solve)?;
+                                    self.db.name_winner(&mut solve)?;
+                                    state.spent_by = Some(solve.txid.clone());
+                                    let kind = match solve.block_height {
+                                        Some(height) => {
+                                            state.spend_confirmed_in =
+                                                Some(self.cli.call(&["getblockhash", &height.to_string()])?);
+                                            EventKind::SolveConfirmed
+                                        }
+                                        None => EventKind::SolveInMempool,
+                                    };
+                                    let mut event = self.event(kind, puzzle, txid, vout);
+                                    event.block_height = solve.block_height;
+                                    event.detail = Some(format!("replaced {}", spend_txid));
+                                    event.solve = Some(solve);
+                                    events.push(event);
+                                }
+                            }
+                            return Ok(state);
+                        }
+                    };
+                    if let Some(block_hash) = tx["blockhash"].as_str() {
+                        let header = self.cli.call_json(&["getblockheader", block_hash])?;
+                        let height = header["height"].as_u64();
+                        let input = solves::spending_input(&tx, txid, vout).unwrap_or(0);
+                        let mut solve = solves::solve_from_tx(&tx, input, &puzzle.target_hash, height, header["time"].as_i64());
+                        self.db.record_solve(puzzle.id, txid, vout, &solve)?;
+                        self.db.name_winner(&mut solve)?;
+                        state.spend_confirmed_in = Some(block_hash.to_string());
+
+                        let mut event = self.event(EventKind::SolveConfirmed, puzzle, txid, vout);
+                        event.block_height = height;
+                        event.solve = Some(solve);
+                        events.push(event);
+                    }
+                }
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Confirmations whose block left the main chain.
+    fn check_reorgs(&mut self, puzzles: &[PuzzleRecord], events: &mut Vec<Event>) -> Result<()> {
+        let mut reorged = vec![];
+        for ((txid, vout), state) in self.outpoints.iter_mut() {
+            for (block, what) in [
+                (&mut state.confirmed_in, "funding"),
+                (&mut state.spend_confirmed_in, "solve"),
+            ] {
+                let Some(hash) = block.clone() else {
+                    continue;
+                };
+                let header = self.cli.call_json(&["getblockheader", &hash])?;
+                if header["confirmations"].as_i64() == Some(-1) {
+                    *block = None;
+                    reorged.push((txid.clone(), *vout, format!("{} block {} left the main chain", what, hash)));
+                }
+            }
+        }
+
+        for (txid, vout, detail) in reorged {
+            let Some(puzzle) = self.puzzle_for_outpoint(puzzles, &txid, vout)? else {
+                continue;
+            };
+            let mut event = self.event(EventKind::Reorged, puzzle, &txid, vout);
+            event.detail = Some(detail);
+            events.push(event);
+        }
+        Ok(())
+    }
+
+    fn puzzle_for_outpoint<'a>(&self, puzzles: &'a [PuzzleRecord], txid: &str, vout: u32) -> Result<Option<&'a PuzzleRecord>> {
+        for puzzle in puzzles {
+            if self.db.funding_outpoints(puzzle.id)?.iter().any(|o| o.txid == txid && o.vout == vout) {
+                return Ok(Some(puzzle));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,176 @@
+/*
+ * SERVER - The HTTP API against a regtest Elements node
+ *
+ * Starts elementsd on liquidregtest with Simplicity active, creates and
+ * funds a puzzle with `puzzle-hunt create`, then calls every route of
+ * the API the way puzzle-server does.
+ *
+ * elementsd is found through ELEMENTSD_EXE or PATH (see the elementsd
+ * crate) and elements-cli next to it, or through ELEMENTS_CLI_PATH.
+ * Without elementsd the test is skipped.
+ */
+
+use elementsd::bitcoincore_rpc::RpcApi;
+use elementsd::{Conf, ElementsD};
+use puzzle_hunt::chain::ElementsCli;
+use puzzle_hunt::server::Api;
+use puzzle_hunt::store::PuzzleStore;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SECRET: &str = "satoshi";
+const WALLET: &str = "default";
+
+/// Keys that must never appear anywhere in a reply.
+const PRIVATE_KEYS: &[&str] = &["secret", "hint_schedule", "height_keys"];
+
+fn start_node() -> Option<(ElementsD, PathBuf)> {
+    let Ok(exe) = elementsd::exe_path() else {
+        eprintln!("elementsd not found (set ELEMENTSD_EXE), skipping");
+        return None;
+    };
+    let cli_path = std::env::var("ELEMENTS_CLI_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| Path::new(&exe).with_file_name("elements-cli"));
+    assert!(cli_path.exists(), "elements-cli not found at {}", cli_path.display());
+
+    let mut conf = Conf::new(None);
+    // Simplicity is not active on regtest by default
+    conf.0.args.push("-evbparams=simplicity:-1:::");
+    let node = ElementsD::with_conf(exe, &conf).expect("elementsd should start");
+    Some((node, cli_path))
+}
+
+fn rpc(node: &ElementsD, method: &str, args: &[Value]) -> Value {
+    node.client().call::<Value>(method, args).unwrap_or_else(|e| panic!("{} failed: {}", method, e))
+}
+
+fn mine(node: &ElementsD, blocks: u64) {
+    let address = rpc(node, "getnewaddress", &[]);
+    rpc(node, "generatetoaddress", &[blocks.into(), address]);
+}
+
+/// Point puzzle-hunt (this process and the binaries it runs) at the node
+/// and at a fresh working directory.
+fn configure(node: &ElementsD, cli_path: &Path) -> PathBuf {
+    let cookie = &node.params().cookie_file;
+    let data_dir = cookie.parent().and_then(Path::parent).expect("cookie is in <datadir>/<chain>/");
+    let work_dir = std::env::temp_dir().join(format!("puzzle-hunt-server-test-{}", std::process::id()));
+    std::fs::create_dir_all(&work_dir).unwrap();
+
+    std::env::set_var("ELEMENTS_CLI_PATH", cli_path);
+    std::env::set_var("ELEMENTS_CHAIN", "liquidregtest");
+    std::env::set_var("ELEMENTS_DATA_DIR", data_dir);
+    std::env::set_var("RPC_PORT", node.params().rpc_socket.port().to_string());
+    std::env::set_var("WALLET_NAME", WALLET);
+    std::env::set_var("PUZZLE_DB", work_dir.join("puzzles.db"));
+    std::env::set_var("PUZZLE_SECRET_PASSPHRASE", "regtest passphrase");
+    std::env::remove_var("PUZZLE_SECRET_RECIPIENT");
+    for key in ["RPC_USER", "RPC_PASSWORD"] {
+        std::env::remove_var(key);
+    }
+    // Puzzle files are recorded relative to where create ran
+    std::env::set_current_dir(&work_dir).unwrap();
+    work_dir
+}
+
+fn create_puzzle(work_dir: &Path) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_puzzle-hunt"))
+        .args(["--output", "json", "create", "--secret", SECRET, "--amount", "0.1", "--hint", "Bitcoin's creator"])
+        .current_dir(work_dir)
+        .output()
+        .expect("puzzle-hunt should run");
+    let result: Value = serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|_| panic!("create printed no JSON: {}", String::from_utf8_lossy(&output.stderr)));
+    assert_eq!(result["ok"], true, "create failed: {}", result);
+    result
+}
+
+fn assert_no_private_keys(value: &Value) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                assert!(!PRIVATE_KEYS.contains(&key.as_str()), "{} was served: {}", key, value);
+                assert_no_private_keys(value);
+            }
+        }
+        Value::Array(items) => items.iter().for_each(assert_no_private_keys),
+        _ => {}
+    }
+}
+
+#[test]
+fn serves_a_funded_puzzle() {
+    let Some((node, cli_path)) = start_node() else {
+        return;
+    };
+    let work_dir = configure(&node, &cli_path);
+
+    // The initial free coins belong to the wallet once it has rescanned
+    rpc(&node, "rescanblockchain", &[]);
+    mine(&node, 1);
+
+    let created = create_puzzle(&work_dir);
+    let target_hash = created["target_hash"].as_str().unwrap().to_string();
+    let id = &target_hash[2..10];
+    mine(&node, 1);
+
+    let api = Api::new(ElementsCli::from_config().unwrap(), PuzzleStore::open_default().unwrap());
+
+    let health = api.handle("GET", "/health", "");
+    assert_eq!(health.status, 200);
+    assert_eq!(health.body["network"], "liquidregtest");
+    assert_eq!(health.body["node_available"], true);
+
+    let list = api.handle("GET", "/puzzles", "");
+    assert_eq!(list.status, 200);
+    assert_no_private_keys(&list.body);
+    let listed = &list.body["puzzles"][0];
+    assert_eq!(listed["target_hash"], target_hash.as_str());
+    assert_eq!(listed["status"], "active");
+    assert_eq!(listed["pot_sats"], created["amount_sats"]);
+
+    let puzzle = api.handle("GET", &format!("/puzzles/{}", id), "");
+    assert_eq!(puzzle.status, 200);
+    assert_no_private_keys(&puzzle.body);
+    assert!(puzzle.body.get("hash").is_none());
+    assert_eq!(puzzle.body["target_hash"], target_hash.as_str());
+    assert_eq!(puzzle.body["txid"], created["txid"]);
+
+    let program = api.handle("GET", &format!("/puzzles/{}/program", id), "");
+    assert_eq!(program.status, 200);
+    assert_eq!(program.body["cmr"], created["cmr"]);
+    assert_eq!(program.body["address"], created["address"]);
+    assert_eq!(program.body["arguments"]["TARGET_HASH"], target_hash.as_str());
+
+    let to = rpc(&node, "getnewaddress", &[]);
+    let wrong = api.handle(
+        "POST",
+        &format!("/puzzles/{}/solve", id),
+        &serde_json::json!({ "secret": "vitalik", "to": to, "fee_rate": "1", "dry_run": true }).to_string(),
+    );
+    assert_eq!(wrong.status, 400);
+    assert_eq!(wrong.body["error"]["code"], "wrong_secret");
+
+    let dry_run = api.handle(
+        "POST",
+        &format!("/puzzles/{}/solve", id),
+        &serde_json::json!({ "secret": SECRET, "to": to, "fee_rate": "1", "dry_run": true }).to_string(),
+    );
+    assert_eq!(dry_run.status, 200, "dry run failed: {}", dry_run.body);
+    assert_eq!(dry_run.body["dry_run"], true);
+    assert_eq!(dry_run.body["broadcast"], false);
+    assert_eq!(dry_run.body["puzzle_txid"], created["txid"]);
+    assert!(dry_run.body["tx_hex"].as_str().is_some_and(|hex| !hex.is_empty()));
+    assert!(dry_run.body["mempool_accept"].is_object());
+
+    // Nothing was broadcast: the puzzle output is still there
+    let vout = created["vout"].as_u64().unwrap();
+    let utxo = rpc(&node, "gettxout", &[created["txid"].clone(), vout.into(), true.into()]);
+    assert!(!utxo.is_null(), "the dry run spent the puzzle");
+
+    let leaderboard = api.handle("GET", "/leaderboard", "");
+    assert_eq!(leaderboard.status, 200);
+    assert_eq!(leaderboard.body["solved_puzzles"], 0);
+}